@@ -1,5 +1,5 @@
 {
-  description = "A dev shell with Rust nightly";
+  description = "A dev shell with Rust stable";
 
   inputs = {
          nixpkgs.url = "github:NixOS/nixpkgs/nixos-unstable";
@@ -20,12 +20,10 @@
             pkgs.erlang
             pkgs.rebar3
             pkgs.wasm-tools
-            (pkgs.rust-bin.selectLatestNightlyWith ( toolchain:
-              toolchain.default.override {
-                extensions = [ "rust-src" "rust-analyzer" ];
-                targets = [ "wasm32-unknown-unknown" ];
-              }
-            ))
+            (pkgs.rust-bin.stable.latest.default.override {
+              extensions = [ "rust-src" "rust-analyzer" ];
+              targets = [ "wasm32-unknown-unknown" ];
+            })
           ];
 
           shellHook = "exec ${pkgs.fish}/bin/fish";
@@ -0,0 +1,239 @@
+//! Reproducible timings of the solver
+//!
+//! The 9×9 puzzles are the hard ones of `hard-9x9.txt`, the others are
+//! generated from fixed seeds, so that the numbers of two runs compare.
+//! Every size from 4×4 to 49×49 is measured, the search on 36×36 and
+//! 49×49 grids being bounded by a number of steps.
+//! Run with `cargo bench -p sudoku`, and `-- --save-baseline <name>` then
+//! `-- --baseline <name>` to measure a change, and `-- learning` to only
+//! compare the search with and without nogoods, proving the hard 9×9
+//! puzzles unique and generating full 36×36 and 49×49 grids.
+
+use criterion::{BatchSize, BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::{SeedableRng, rngs::SmallRng};
+use std::hint::black_box;
+use sudoku::{Alphabet, Cell, Defer, Pos, RngChild, Size, Storage, Sudoku};
+
+const HARD: &str = include_str!("hard-9x9.txt");
+
+// A puzzle with its solution, and its givens encoded for `decode_grid`
+struct Instance<const N: usize>
+where
+    Size<N>: Storage,
+{
+    name: String,
+    puzzle: Sudoku<N>,
+    solution: Sudoku<N>,
+    encoded: Vec<u8>,
+}
+
+impl<const N: usize> Instance<N>
+where
+    Size<N>: Storage,
+{
+    fn new(name: String, puzzle: Sudoku<N>) -> Self {
+        let solution = puzzle.clone().brute_force((), 0..).next().unwrap();
+        let mut encoded = vec![0; N * N * N * N];
+        let mut mask = [[[[false; N]; N]; N]; N];
+        for pos in Pos::iter::<N>() {
+            mask[pos] = puzzle[pos].len() == 1;
+        }
+        puzzle.encode_grid(&mut encoded, mask);
+        Self {
+            name,
+            puzzle,
+            solution,
+            encoded,
+        }
+    }
+
+    // Puzzles hidden from a scrambled pattern, one per seed
+    fn generated(seeds: std::ops::Range<u64>) -> Vec<Self> {
+        seeds
+            .map(|seed| {
+                let mut rng = SmallRng::seed_from_u64(seed);
+                let full = Sudoku::<N>::generate_shuffled(&mut rng);
+                let mask = full.obfuscate(&mut rng);
+                let mut encoded = vec![0; N * N * N * N];
+                full.encode_grid(&mut encoded, mask);
+                Self::new(
+                    format!("seed {seed}"),
+                    Sudoku::decode_grid(&encoded).unwrap(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn corpus() -> Vec<Instance<3>> {
+    let alphabet = Alphabet::standard();
+    Sudoku::<3>::from_collection(HARD, &alphabet)
+        .enumerate()
+        .map(|(i, puzzle)| Instance::new(format!("hard {}", i + 1), puzzle.unwrap()))
+        .collect()
+}
+
+// The search is given at most `budget` steps
+fn bench_size<const N: usize>(c: &mut Criterion, instances: &[Instance<N>], budget: usize)
+where
+    Size<N>: Storage,
+{
+    let size = format!("{0}x{0}", N * N);
+
+    let mut group = c.benchmark_group(format!("brute_force/{size}"));
+    for instance in instances {
+        // the search goes on after the first solution, to prove it is unique
+        group.bench_function(&instance.name, |b| {
+            b.iter_batched_ref(
+                || instance.puzzle.clone(),
+                |grid| grid.brute_force((), 0..budget).take(2).count(),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("remove_all");
+    let solution = &instances[0].solution;
+    // filling an empty grid cell by cell cascades through every house
+    group.bench_function(BenchmarkId::from_parameter(&size), |b| {
+        b.iter(|| {
+            let mut grid = Sudoku::<N>::default();
+            let mut defer = Defer::new();
+            for pos in Pos::iter::<N>() {
+                let cell: Cell<N> = black_box(solution[pos]);
+                grid.remove_all(!cell, pos, &mut defer).unwrap();
+            }
+            grid
+        })
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("obfuscate");
+    group.bench_function(BenchmarkId::from_parameter(&size), |b| {
+        b.iter(|| solution.obfuscate(SmallRng::seed_from_u64(0)))
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("decode_grid");
+    group.bench_function(BenchmarkId::from_parameter(&size), |b| {
+        b.iter(|| Sudoku::<N>::decode_grid(black_box(&instances[0].encoded)).unwrap())
+    });
+    group.finish();
+}
+
+// Steps taken to prove the solution of the puzzle unique, as counted by the ttl
+fn uniqueness<const N: usize>(puzzle: &Sudoku<N>, capacity: usize) -> usize
+where
+    Size<N>: Storage,
+{
+    let count = std::cell::Cell::new(0);
+    let ttl = std::iter::repeat(0).inspect(|_| count.set(count.get() + 1));
+    puzzle
+        .clone()
+        .brute_force_learning((), ttl, capacity)
+        .take(2)
+        .count();
+    count.get()
+}
+
+// Nogood learning against the plain search, proving hard 9×9 puzzles unique
+//
+// The steps taken and the puzzles on which learning saves none are printed first.
+fn bench_learning_corpus(c: &mut Criterion, instances: &[Instance<3>]) {
+    let plain: Vec<usize> = instances
+        .iter()
+        .map(|instance| uniqueness(&instance.puzzle, 0))
+        .collect();
+    let learning: Vec<usize> = instances
+        .iter()
+        .map(|instance| uniqueness(&instance.puzzle, 64))
+        .collect();
+    let unsaved = plain.iter().zip(&learning).filter(|(a, b)| b >= a).count();
+    eprintln!(
+        "learning/9x9: capacity 0, {} steps, capacity 64, {} steps, nothing saved on {unsaved} of {} puzzles",
+        plain.iter().sum::<usize>(),
+        learning.iter().sum::<usize>(),
+        instances.len(),
+    );
+    let mut group = c.benchmark_group("learning/9x9");
+    for capacity in [0, 64] {
+        group.bench_function(BenchmarkId::from_parameter(capacity), |b| {
+            b.iter(|| {
+                for instance in instances {
+                    black_box(uniqueness(&instance.puzzle, capacity));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+// Steps taken to generate a full grid from the seed, as counted by the
+// ttl, and whether it was generated before running out of it
+fn generation<const N: usize>(seed: u64, capacity: usize) -> (usize, bool)
+where
+    Size<N>: Storage,
+{
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let count = std::cell::Cell::new(0);
+    let ttl = (0..Sudoku::<N>::TTL).inspect(|_| count.set(count.get() + 1));
+    let found = Sudoku::<N>::default()
+        .brute_force_learning(rng.rng_child(), ttl, capacity)
+        .next()
+        .is_some();
+    (count.get(), found)
+}
+
+// Nogood learning against the plain search, generating large full grids
+//
+// The steps taken and the attempts running out of their time to live
+// are printed first, over more seeds than are timed, since time alone
+// doesn't tell whether learning pruned anything.
+fn bench_learning<const N: usize>(c: &mut Criterion, seeds: std::ops::Range<u64>, timed: u64)
+where
+    Size<N>: Storage,
+{
+    const CAPACITIES: [usize; 2] = [0, 64];
+    let size = format!("{0}x{0}", N * N);
+
+    for capacity in CAPACITIES {
+        let (mut taken, mut exhausted) = (0, 0);
+        for seed in seeds.clone() {
+            let (steps, found) = generation::<N>(seed, capacity);
+            taken += steps;
+            exhausted += usize::from(!found);
+        }
+        eprintln!(
+            "learning/{size}: capacity {capacity}, {taken} steps, {exhausted} of {} attempts exhausted",
+            seeds.end - seeds.start,
+        );
+    }
+    let mut group = c.benchmark_group(format!("learning/{size}"));
+    group.sample_size(10);
+    for capacity in CAPACITIES {
+        group.bench_function(BenchmarkId::from_parameter(capacity), |b| {
+            b.iter(|| {
+                for seed in 0..timed {
+                    black_box(generation::<N>(seed, capacity));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn solver(c: &mut Criterion) {
+    bench_size::<2>(c, &Instance::generated(0..3), usize::MAX);
+    bench_size::<3>(c, &corpus(), usize::MAX);
+    bench_size::<4>(c, &Instance::generated(0..3), usize::MAX);
+    bench_size::<5>(c, &Instance::generated(0..3), usize::MAX);
+    bench_size::<6>(c, &Instance::generated(0..2), 1 << 12);
+    bench_size::<7>(c, &Instance::generated(0..2), 1 << 12);
+    bench_learning_corpus(c, &corpus());
+    bench_learning::<6>(c, 0..32, 4);
+    bench_learning::<7>(c, 0..32, 4);
+}
+
+criterion_group!(benches, solver);
+criterion_main!(benches);
@@ -0,0 +1,146 @@
+/// The symbols a grid is written with, one per value
+///
+/// `_` and `.` are kept for blanks and can't be symbols, nor can whitespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: Vec<char>,
+}
+
+const STANDARD: &str =
+    "1234567890ABCDEFGHIJKLMNOPQRSTUVWXYZΨΩΦΔΞΓΠΣДБЏШЧカサタホルモテツウセオアワヌス";
+
+impl Alphabet {
+    /// An alphabet of the given symbols, `None` if one is repeated or reserved
+    pub fn new(symbols: impl IntoIterator<Item = char>) -> Option<Self> {
+        let mut alphabet = Self {
+            symbols: Vec::new(),
+        };
+        for symbol in symbols {
+            if symbol.is_whitespace()
+                || matches!(symbol, '_' | '.')
+                || alphabet.value(symbol).is_some()
+            {
+                return None;
+            }
+            alphabet.symbols.push(symbol);
+        }
+        Some(alphabet)
+    }
+
+    /// `1` to `9`, then `0`, the latin letters and some greek, cyrillic
+    /// and katakana ones, enough for 64×64 grids
+    pub fn standard() -> Self {
+        Self::new(STANDARD.chars()).unwrap()
+    }
+
+    /// `1` to `9`, then `A` to `Z`
+    pub fn digits() -> Self {
+        Self::new(('1'..='9').chain('A'..='Z')).unwrap()
+    }
+
+    /// `0` to `9`, then `A` to `F`, the first value being `0`
+    pub fn hex() -> Self {
+        Self::new(('0'..='9').chain('A'..='F')).unwrap()
+    }
+
+    /// `A` to `Z`
+    pub fn letters() -> Self {
+        Self::new('A'..='Z').unwrap()
+    }
+
+    /// The letters of a word, in upper case, `None` if one is repeated
+    ///
+    /// A 9 letter word makes a 9×9 wordoku.
+    pub fn wordoku(word: &str) -> Option<Self> {
+        Self::new(word.chars().flat_map(char::to_uppercase))
+    }
+
+    /// The number of symbols
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Enough symbols for grids of size `n`
+    pub fn covers(&self, n: usize) -> bool {
+        self.len() >= n * n
+    }
+
+    pub fn symbol(&self, value: u32) -> Option<char> {
+        self.symbols.get(value as usize).copied()
+    }
+
+    pub fn value(&self, symbol: char) -> Option<u32> {
+        self.symbols
+            .iter()
+            .position(|s| *s == symbol)
+            .map(|value| value as u32)
+    }
+
+    /// The number of columns the symbol of `value` takes in a terminal
+    pub fn width(&self, value: u32) -> Option<u32> {
+        self.symbol(value).map(char_width)
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+// East asian wide characters and emoji take two columns
+fn char_width(c: char) -> u32 {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F300..=0x1F64F
+        | 0x1F900..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+#[test]
+fn test_char_mapping() {
+    let alphabet = Alphabet::standard();
+    assert_eq!(alphabet.len(), 64);
+    for value in 0..64 {
+        let char = alphabet.symbol(value).unwrap();
+        assert_eq!(alphabet.value(char).unwrap(), value);
+    }
+    assert_eq!(alphabet.symbol(9), Some('0'));
+    assert_eq!(alphabet.width(48), Some(1));
+    assert_eq!(alphabet.width(49), Some(2));
+    assert_eq!(alphabet.symbol(64), None);
+}
+
+#[test]
+fn presets_and_custom() {
+    assert_eq!(Alphabet::digits().symbol(9), Some('A'));
+    assert_eq!(Alphabet::hex().value('0'), Some(0));
+    assert_eq!(Alphabet::hex().len(), 16);
+    assert_eq!(Alphabet::letters().symbol(25), Some('Z'));
+
+    let wordoku = Alphabet::wordoku("Blackhorse").unwrap();
+    assert_eq!(wordoku.value('K'), Some(4));
+    assert!(Alphabet::wordoku("sudoku").is_none());
+
+    let emoji = Alphabet::new(['🍎', '🍌', '🍒', '🍇']).unwrap();
+    assert!(emoji.covers(2));
+    assert_eq!(emoji.width(0), Some(2));
+    assert!(Alphabet::new(['1', '_']).is_none());
+}
@@ -0,0 +1,381 @@
+use std::io::Write;
+
+use rand::Rng;
+
+use crate::{
+    Alphabet, Choose, Grade, LoadingError, Logic, Markings, Pos, RngChild, Step, Style, Sudoku,
+    SvgOptions, Transform,
+};
+
+/// A chooser usable for every grid size of [AnySudoku]
+pub trait ChooseAny:
+    Choose<1>
+    + Choose<2>
+    + Choose<3>
+    + Choose<4>
+    + Choose<5>
+    + Choose<6>
+    + Choose<7>
+    + Choose<8>
+    + Choose<9>
+    + Choose<10>
+    + Choose<11>
+{
+}
+impl<C> ChooseAny for C where
+    C: Choose<1>
+        + Choose<2>
+        + Choose<3>
+        + Choose<4>
+        + Choose<5>
+        + Choose<6>
+        + Choose<7>
+        + Choose<8>
+        + Choose<9>
+        + Choose<10>
+        + Choose<11>
+{
+}
+
+macro_rules! any_sudoku {
+    ($($n:literal => $variant:ident),* $(,)?) => {
+        /// A [Sudoku] whose size is only known at runtime
+        ///
+        /// Masks are given as one `bool` per cell, in the order of [Pos::iter].
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub enum AnySudoku {
+            $($variant(Box<Sudoku<$n>>),)*
+        }
+
+        // Run `$body` with `$grid` bound to the inner `Sudoku<N>`,
+        // `N` is available as a constant
+        macro_rules! each {
+            ($any:expr, $grid:pat => $body:expr) => {
+                match $any {
+                    $(AnySudoku::$variant($grid) => {
+                        #[allow(unused)]
+                        const N: usize = $n;
+                        framed(move || $body)
+                    })*
+                }
+            };
+        }
+
+        // Run `$body` with `N` set to `$size`, or evaluate to `None`
+        macro_rules! with_size {
+            ($size:expr, $body:expr) => {
+                match $size {
+                    $($n => {
+                        const N: usize = $n;
+                        Some(framed(move || $body))
+                    })*
+                    _ => None,
+                }
+            };
+        }
+
+        $(
+            impl From<Sudoku<$n>> for AnySudoku {
+                fn from(grid: Sudoku<$n>) -> Self {
+                    AnySudoku::$variant(Box::new(grid))
+                }
+            }
+        )*
+    };
+}
+
+any_sudoku![
+    1 => N1,
+    2 => N2,
+    3 => N3,
+    4 => N4,
+    5 => N5,
+    6 => N6,
+    7 => N7,
+    8 => N8,
+    9 => N9,
+    10 => N10,
+    11 => N11,
+];
+
+impl AnySudoku {
+    /// The biggest supported size, `N` being the size of a square
+    pub const MAX_SIZE: usize = 11;
+
+    /// An empty grid, if the size is supported
+    pub fn new(size: usize) -> Option<Self> {
+        with_size!(size, Sudoku::<N>::default().into())
+    }
+
+    /// The size of a square
+    pub fn size(&self) -> usize {
+        each!(self, _ => N)
+    }
+
+    /// See [Sudoku::TTL]
+    pub fn ttl(&self) -> usize {
+        each!(self, _ => Sudoku::<N>::TTL)
+    }
+
+    /// The number of cells of the grid
+    pub fn cell_count(&self) -> usize {
+        self.size().pow(4)
+    }
+
+    /// The size whose grid has `cells` cells
+    pub fn size_of_len(cells: usize) -> Option<usize> {
+        (1..=Self::MAX_SIZE).find(|n| n.pow(4) == cells)
+    }
+
+    /// See [Sudoku::from_values], the size is deduced from the number of cells
+    pub fn from_values(values: &[Option<u32>]) -> Result<Self, LoadingError> {
+        let received = values.len();
+        let size = Self::size_of_len(received).ok_or(LoadingError::InvalidSize { received })?;
+        with_size!(size, Sudoku::<N>::from_values(values).map(Self::from))
+            .unwrap_or(Err(LoadingError::InvalidSize { received }))
+    }
+
+    /// See [Logic::grade], the size is deduced from the number of cells
+    pub fn grade(values: &[Option<u32>]) -> Result<Grade, LoadingError> {
+        let received = values.len();
+        let size = Self::size_of_len(received).ok_or(LoadingError::InvalidSize { received })?;
+        with_size!(size, Logic::<N>::from_values(values).map(Logic::grade))
+            .unwrap_or(Err(LoadingError::InvalidSize { received }))
+    }
+
+    /// Take the steps of [Logic] one by one, calling `each` with the step
+    /// and the candidates it leaves, and sum them up
+    pub fn explain(
+        values: &[Option<u32>],
+        mut each: impl FnMut(&Step, &AnySudoku),
+    ) -> Result<Grade, LoadingError> {
+        let received = values.len();
+        let size = Self::size_of_len(received).ok_or(LoadingError::InvalidSize { received })?;
+        with_size!(size, {
+            let mut logic = Logic::<N>::from_values(values)?;
+            let mut grade = Grade::default();
+            while let Some(step) = logic.step() {
+                each(&step, &Self::from(logic.grid().clone()));
+                grade.record(&step);
+            }
+            grade.solved = logic.is_solved();
+            Ok(grade)
+        })
+        .unwrap_or(Err(LoadingError::InvalidSize { received }))
+    }
+
+    /// See [Sudoku::decode_grid]
+    pub fn decode_grid(size: usize, src: &[u8]) -> Option<Self> {
+        with_size!(size, Sudoku::<N>::decode_grid(src).map(Self::from)).flatten()
+    }
+
+    /// See [Sudoku::encode_grid]
+    pub fn encode_grid(&self, dst: &mut [u8], mask: &[bool]) {
+        each!(self, grid => grid.encode_grid(dst, mask_from_slice::<N>(mask)))
+    }
+
+    /// The value of every cell, in the order of [Pos::iter]
+    pub fn values(&self) -> Vec<Option<u32>> {
+        each!(self, grid => Pos::iter::<N>().map(|pos| grid[pos].get_value()).collect())
+    }
+
+    /// See [Sudoku::is_accepting]
+    pub fn is_accepting(&self) -> bool {
+        each!(self, grid => grid.is_accepting())
+    }
+
+    /// See [Sudoku::brute_force_learning]
+    pub fn solutions<'a>(
+        &'a mut self,
+        chooser: impl ChooseAny + 'a,
+        ttl: impl IntoIterator<Item = usize, IntoIter: 'a>,
+        capacity: usize,
+    ) -> Box<dyn Iterator<Item = AnySudoku> + 'a> {
+        each!(self, grid => Box::new(
+            grid.brute_force_learning(chooser, ttl, capacity).map(AnySudoku::from)
+        ))
+    }
+
+    /// See [Sudoku::generate_full_learning]
+    pub fn generate_full<R>(size: usize, rng: &mut R, capacity: usize) -> Option<Self>
+    where
+        R: RngChild + ChooseAny,
+    {
+        with_size!(
+            size,
+            Sudoku::<N>::generate_full_learning(rng, capacity).map(Self::from)
+        )
+        .flatten()
+    }
+
+    /// See [Sudoku::generate_shuffled]
+    pub fn generate_shuffled(size: usize, rng: impl Rng) -> Option<Self> {
+        with_size!(size, Sudoku::<N>::generate_shuffled(rng).into())
+    }
+
+    /// See [Sudoku::obfuscate]
+    pub fn obfuscate(&self, rng: impl Rng) -> Vec<bool> {
+        each!(self, grid => mask_to_vec::<N>(grid.obfuscate(rng)))
+    }
+
+    /// A random isomorphic copy of the grid, with the mask moved along,
+    /// see [Transform::random]
+    pub fn shuffle(&self, mask: &[bool], rng: impl Rng) -> (Self, Vec<bool>) {
+        each!(self, grid => {
+            let transform = Transform::<N>::random(rng);
+            (
+                Self::from(transform.apply(grid)),
+                mask_to_vec::<N>(transform.apply_mask(mask_from_slice::<N>(mask))),
+            )
+        })
+    }
+
+    /// A mask showing every cell
+    pub fn mask_full(&self) -> Vec<bool> {
+        vec![true; self.cell_count()]
+    }
+
+    /// See [Sudoku::print]
+    pub fn print(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        each!(self, grid => grid.print(writer, mask_from_slice::<N>(mask), alphabet))
+    }
+
+    /// See [Sudoku::print_solution]
+    pub fn print_solution(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        givens: &[bool],
+        alphabet: &Alphabet,
+        style: Style,
+    ) -> Result<(), std::io::Error> {
+        each!(self, grid => grid.print_solution(
+            writer,
+            mask_from_slice::<N>(mask),
+            mask_from_slice::<N>(givens),
+            alphabet,
+            style
+        ))
+    }
+
+    /// See [Sudoku::print_candidates]
+    pub fn print_candidates(
+        &self,
+        writer: impl Write,
+        alphabet: &Alphabet,
+        style: Style,
+    ) -> Result<(), std::io::Error> {
+        each!(self, grid => grid.print_candidates(writer, alphabet, style))
+    }
+
+    /// See [Sudoku::write_svg]
+    pub fn write_svg(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        alphabet: &Alphabet,
+        options: &SvgOptions,
+    ) -> Result<(), std::io::Error> {
+        each!(self, grid => grid.write_svg(writer, mask_from_slice::<N>(mask), alphabet, options))
+    }
+
+    /// See [Sudoku::write_svg_marked]
+    pub fn write_svg_marked(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        alphabet: &Alphabet,
+        options: &SvgOptions,
+        markings: &Markings,
+    ) -> Result<(), std::io::Error> {
+        each!(self, grid => {
+            grid.write_svg_marked(writer, mask_from_slice::<N>(mask), alphabet, options, markings)
+        })
+    }
+}
+
+// The body of each size gets its own stack frame, otherwise debug builds
+// reserve room for the grids of every size at once
+fn framed<T>(body: impl FnOnce() -> T) -> T {
+    body()
+}
+
+fn mask_from_slice<const N: usize>(mask: &[bool]) -> [[[[bool; N]; N]; N]; N] {
+    assert!(mask.len() >= N * N * N * N);
+    let mut grid = [[[[false; N]; N]; N]; N];
+    for (pos, &shown) in Pos::iter::<N>().zip(mask) {
+        grid[pos] = shown;
+    }
+    grid
+}
+
+fn mask_to_vec<const N: usize>(mask: [[[[bool; N]; N]; N]; N]) -> Vec<bool> {
+    Pos::iter::<N>().map(|pos| mask[pos]).collect()
+}
+
+#[test]
+fn dispatch_by_size() {
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    assert!(AnySudoku::new(0).is_none());
+    assert!(AnySudoku::new(12).is_none());
+    assert_eq!(AnySudoku::new(3).unwrap().size(), 3);
+    assert_eq!(AnySudoku::new(3).unwrap().ttl(), Sudoku::<3>::TTL);
+    assert_eq!(AnySudoku::size_of_len(256), Some(4));
+
+    let mut rng = SmallRng::seed_from_u64(7);
+    let full = AnySudoku::generate_full(2, &mut rng, 0).unwrap();
+    assert!(full.is_accepting());
+    let mask = full.obfuscate(&mut rng);
+    let mut encoded = [0u8; 16];
+    full.encode_grid(&mut encoded, &mask);
+    let mut puzzle = AnySudoku::decode_grid(2, &encoded).unwrap();
+    let solution = puzzle.solutions((), 0.., 0).next().unwrap();
+    assert_eq!(solution.values(), full.values());
+
+    let values = full.values();
+    assert_eq!(AnySudoku::from_values(&values).unwrap().values(), values);
+    assert!(matches!(
+        AnySudoku::from_values(&[Some(4); 16]),
+        Err(LoadingError::InvalidValue {
+            value: 4,
+            row: 0,
+            col: 0
+        })
+    ));
+    assert!(matches!(
+        AnySudoku::from_values(&values[1..]),
+        Err(LoadingError::InvalidSize { received: 15 })
+    ));
+}
+
+#[test]
+fn numbers_beyond_symbols() {
+    let mut src = vec!["_"; 81 * 81];
+    src[0] = "81";
+    src[81 * 81 - 1] = "7";
+    let grid = AnySudoku::from_numbers(&src.join(" ")).unwrap();
+    assert_eq!(grid.size(), 9);
+    let values = grid.values();
+    assert_eq!(values[0], Some(80));
+    assert_eq!(values[81 * 81 - 1], Some(6));
+
+    let mut printed = Vec::new();
+    grid.print(&mut printed, &grid.mask_full(), &Alphabet::standard())
+        .unwrap();
+    let printed = String::from_utf8(printed).unwrap();
+    let lines: Vec<&str> = printed.lines().collect();
+    assert!(lines[1].starts_with("┃ 81 │    │"));
+    assert!(lines[lines.len() - 2].ends_with("│  7 ┃"));
+    assert!(lines.iter().all(|line| line.chars().count() == 81 * 5 + 1));
+
+    let blanks = AnySudoku::from_numbers("1 _ _ _\n_ _ _ _\n0 0 0 0\n_ _ _ 4").unwrap();
+    assert_eq!(blanks.size(), 2);
+    assert_eq!(blanks.values()[0], Some(0));
+    assert_eq!(blanks.values()[15], Some(3));
+}
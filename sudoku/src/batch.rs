@@ -0,0 +1,177 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::AnySudoku;
+
+/// How [solve_batch] goes through a collection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchOptions {
+    /// Number of worker threads, 0 for one per available core
+    pub threads: usize,
+    /// Stop counting the solutions of a puzzle past this many
+    pub max_solutions: usize,
+    /// Nogoods remembered during each search, see [Sudoku::brute_force_learning](crate::Sudoku::brute_force_learning)
+    pub capacity: usize,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        Self {
+            threads: 1,
+            max_solutions: 2,
+            capacity: 0,
+        }
+    }
+}
+
+/// The outcome of one puzzle of a batch
+#[derive(Debug, Clone)]
+pub struct Solved {
+    /// The first solution found, if any
+    pub solution: Option<AnySudoku>,
+    /// The number of solutions, at most [BatchOptions::max_solutions]
+    pub count: usize,
+    pub elapsed: Duration,
+}
+
+/// Solve every puzzle, the results being in the order of the puzzles
+///
+/// The solutions are searched without randomness, so that runs are comparable.
+pub fn solve_batch(puzzles: &[AnySudoku], options: &BatchOptions) -> Vec<Solved> {
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism().map_or(1, usize::from),
+        threads => threads,
+    }
+    .min(puzzles.len())
+    .max(1);
+
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut solved = Vec::new();
+        loop {
+            let index = next.fetch_add(1, Ordering::Relaxed);
+            let Some(puzzle) = puzzles.get(index) else {
+                return solved;
+            };
+            solved.push((index, solve_one(puzzle, options)));
+        }
+    };
+    let mut solved: Vec<(usize, Solved)> = match threads {
+        1 => work(),
+        _ => std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads).map(|_| scope.spawn(work)).collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("a solver thread panicked"))
+                .collect()
+        }),
+    };
+    solved.sort_unstable_by_key(|(index, _)| *index);
+    solved.into_iter().map(|(_, solved)| solved).collect()
+}
+
+fn solve_one(puzzle: &AnySudoku, options: &BatchOptions) -> Solved {
+    let start = Instant::now();
+    let mut grid = puzzle.clone();
+    let mut solutions = grid.solutions((), std::iter::repeat(0), options.capacity);
+    let solution = solutions.next();
+    let count = match solution {
+        Some(_) => {
+            1 + solutions
+                .take(options.max_solutions.saturating_sub(1))
+                .count()
+        }
+        None => 0,
+    };
+    Solved {
+        solution,
+        count,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Aggregate figures of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchSummary {
+    pub puzzles: usize,
+    /// Puzzles without any solution
+    pub failures: usize,
+    /// Puzzles with more than one solution
+    pub ambiguous: usize,
+    /// Wall-clock time of the whole batch
+    pub elapsed: Duration,
+    /// Indices of the slowest puzzles, the slowest first
+    pub slowest: Vec<usize>,
+}
+
+impl BatchSummary {
+    /// Sum up the results of [solve_batch], keeping the `slowest` slowest puzzles
+    pub fn new(solved: &[Solved], elapsed: Duration, slowest: usize) -> Self {
+        let mut order: Vec<usize> = (0..solved.len()).collect();
+        order.sort_by_key(|&index| std::cmp::Reverse(solved[index].elapsed));
+        order.truncate(slowest);
+        Self {
+            puzzles: solved.len(),
+            failures: solved.iter().filter(|solved| solved.count == 0).count(),
+            ambiguous: solved.iter().filter(|solved| solved.count > 1).count(),
+            elapsed,
+            slowest: order,
+        }
+    }
+
+    pub fn per_second(&self) -> f64 {
+        self.puzzles as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+}
+
+#[test]
+fn batch_in_order() {
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    let mut rng = SmallRng::seed_from_u64(5);
+    let mut puzzles = Vec::new();
+    for _ in 0..8 {
+        let full = AnySudoku::generate_full(3, &mut rng, 0).unwrap();
+        let mask = full.obfuscate(&mut rng);
+        let values: Vec<Option<u32>> = full
+            .values()
+            .into_iter()
+            .zip(mask)
+            .map(|(value, shown)| value.filter(|_| shown))
+            .collect();
+        puzzles.push((AnySudoku::from_values(&values).unwrap(), full));
+    }
+    let empty = AnySudoku::new(3).unwrap();
+    let mut grids: Vec<AnySudoku> = puzzles.iter().map(|(puzzle, _)| puzzle.clone()).collect();
+    grids.push(empty);
+
+    let options = BatchOptions {
+        threads: 3,
+        max_solutions: 5,
+        capacity: 0,
+    };
+    let solved = solve_batch(&grids, &options);
+    assert_eq!(solved.len(), 9);
+    for ((_, full), solved) in puzzles.iter().zip(&solved) {
+        assert_eq!(solved.count, 1);
+        assert_eq!(solved.solution.as_ref().unwrap().values(), full.values());
+    }
+    assert_eq!(solved[8].count, 5);
+
+    let sequential = solve_batch(&grids, &BatchOptions::default());
+    let counts: Vec<usize> = sequential.iter().map(|solved| solved.count).collect();
+    assert_eq!(counts, [1, 1, 1, 1, 1, 1, 1, 1, 2]);
+
+    let summary = BatchSummary::new(&solved, Duration::from_secs(2), 3);
+    assert_eq!(summary.puzzles, 9);
+    assert_eq!(summary.failures, 0);
+    assert_eq!(summary.ambiguous, 1);
+    assert_eq!(summary.slowest.len(), 3);
+    assert!(
+        summary
+            .slowest
+            .windows(2)
+            .all(|pair| solved[pair[0]].elapsed >= solved[pair[1]].elapsed)
+    );
+    assert_eq!(summary.per_second(), 4.5);
+}
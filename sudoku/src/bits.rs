@@ -0,0 +1,81 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+/// The storage of the candidates of a [Cell](crate::Cell), one bit per value
+pub trait Bits:
+    Copy
+    + Eq
+    + Hash
+    + Debug
+    + BitAnd<Output = Self>
+    + BitAndAssign
+    + BitOr<Output = Self>
+    + BitOrAssign
+    + Not<Output = Self>
+{
+    /// No bit set
+    const ZERO: Self;
+    /// Number of bits available
+    const BITS: u32;
+
+    /// Only the bit `index`
+    fn bit(index: u32) -> Self;
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    fn leading_zeros(self) -> u32;
+}
+
+macro_rules! bits {
+    ($($t:ty),*) => {$(
+        impl Bits for $t {
+            const ZERO: Self = 0;
+            const BITS: u32 = <$t>::BITS;
+
+            #[inline]
+            fn bit(index: u32) -> Self {
+                1 << index
+            }
+            #[inline]
+            fn count_ones(self) -> u32 {
+                <$t>::count_ones(self)
+            }
+            #[inline]
+            fn trailing_zeros(self) -> u32 {
+                <$t>::trailing_zeros(self)
+            }
+            #[inline]
+            fn leading_zeros(self) -> u32 {
+                <$t>::leading_zeros(self)
+            }
+        }
+    )*};
+}
+
+bits!(u16, u32, u64, u128);
+
+/// Grid size `N`, used to pick the storage of its cells
+pub struct Size<const N: usize>;
+
+/// The grid sizes that have a cell storage
+///
+/// The smallest integer holding `N*N` bits is used, up to `u128` for `N = 11`.
+pub trait Storage {
+    type Bits: Bits;
+    /// The `N*N` lowest bits set
+    const FULL: Self::Bits;
+}
+
+macro_rules! storage {
+    ($t:ty: $($n:literal),*) => {$(
+        impl Storage for Size<$n> {
+            type Bits = $t;
+            const FULL: $t = !<$t>::MAX.unbounded_shl($n * $n);
+        }
+    )*};
+}
+
+storage!(u16: 1, 2, 3, 4);
+storage!(u32: 5);
+storage!(u64: 6, 7, 8);
+storage!(u128: 9, 10, 11);
@@ -0,0 +1,496 @@
+use std::io::Write;
+
+use crate::{Alphabet, Cell, Defer, Pos, Shade, Size, Storage, Style, Sudoku, peers};
+
+/// A grid being filled by a player
+///
+/// It remembers the givens of the puzzle and the entries of the player,
+/// in the order they were placed, with the moves each one pushed on the grid.
+/// Candidates always reflect the givens and the current entries.
+#[derive(Debug, Clone)]
+pub struct Board<const N: usize>
+where
+    Size<N>: Storage,
+{
+    grid: Sudoku<N>,
+    placements: Vec<Placement>,
+    /// The index of the placement of each cell, in the order of [Pos::iter]
+    placed: Vec<Option<usize>>,
+    defer: Defer<N>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    pos: Pos,
+    value: u32,
+    given: bool,
+    /// Number of moves pushed on the grid when placed
+    moved: usize,
+}
+
+/// Why an edit of the [Board] was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditError {
+    /// The cell holds a given of the puzzle
+    Given { pos: Pos },
+    /// The value is not in `0..N*N`
+    InvalidValue { value: u32 },
+    /// A cell of the same row, column or square already holds the value
+    Clash { pos: Pos, other: Pos, value: u32 },
+    /// The value was ruled out by the placed values, without a direct clash
+    Contradiction { pos: Pos, value: u32 },
+}
+
+impl<const N: usize> Board<N>
+where
+    Size<N>: Storage,
+{
+    /// A board with the given values, which can't be edited afterward
+    pub fn new(givens: impl IntoIterator<Item = (Pos, u32)>) -> Result<Self, EditError> {
+        let mut board = Self {
+            grid: Sudoku::default(),
+            placements: Vec::new(),
+            placed: vec![None; N * N * N * N],
+            defer: Defer::new(),
+        };
+        for (pos, value) in givens {
+            if board.is_given(pos) {
+                return Err(EditError::Given { pos });
+            }
+            board.place(pos, value, true)?;
+        }
+        Ok(board)
+    }
+
+    /// A board whose givens are the determined cells of `grid` selected by `mask`
+    pub fn from_grid(grid: &Sudoku<N>, mask: [[[[bool; N]; N]; N]; N]) -> Result<Self, EditError> {
+        Self::new(
+            Pos::iter::<N>()
+                .filter(|pos| mask[*pos])
+                .filter_map(|pos| Some((pos, grid[pos].get_value()?))),
+        )
+    }
+
+    /// The grid with the candidates left by the givens and the entries
+    pub fn grid(&self) -> &Sudoku<N> {
+        &self.grid
+    }
+
+    /// The value placed in the cell, given or entered
+    pub fn value(&self, pos: Pos) -> Option<u32> {
+        self.placement(pos).map(|i| self.placements[i].value)
+    }
+
+    pub fn is_given(&self, pos: Pos) -> bool {
+        self.placement(pos)
+            .is_some_and(|i| self.placements[i].given)
+    }
+
+    pub fn givens(&self) -> impl Iterator<Item = (Pos, u32)> + '_ {
+        self.placements
+            .iter()
+            .filter(|p| p.given)
+            .map(|p| (p.pos, p.value))
+    }
+
+    /// The entries of the player, in the order they were placed
+    pub fn entries(&self) -> impl Iterator<Item = (Pos, u32)> + '_ {
+        self.placements
+            .iter()
+            .filter(|p| !p.given)
+            .map(|p| (p.pos, p.value))
+    }
+
+    /// The mask of the givens, as expected by [Sudoku::print]
+    pub fn givens_mask(&self) -> [[[[bool; N]; N]; N]; N] {
+        let mut mask = [[[[false; N]; N]; N]; N];
+        for (pos, _) in self.givens() {
+            mask[pos] = true;
+        }
+        mask
+    }
+
+    /// Print the givens and the entries, in their own shade
+    pub fn print(
+        &self,
+        writer: impl Write,
+        alphabet: &Alphabet,
+        style: Style,
+    ) -> Result<(), std::io::Error> {
+        self.grid.print_styled(writer, alphabet, style, |pos, _| {
+            let placement = self.placements[self.placement(pos)?];
+            let shade = if placement.given {
+                Shade::Given
+            } else {
+                Shade::Entry
+            };
+            Some((placement.value, shade))
+        })
+    }
+
+    /// Every cell holds a given or an entry
+    pub fn is_complete(&self) -> bool {
+        self.placements.len() == N * N * N * N
+    }
+
+    /// Enter a value in the cell, replacing the previous entry
+    ///
+    /// On failure, the board is left unchanged.
+    pub fn set_value(&mut self, pos: Pos, value: u32) -> Result<(), EditError> {
+        if value >= Cell::<N>::R {
+            return Err(EditError::InvalidValue { value });
+        }
+        match self.placement(pos) {
+            Some(i) if self.placements[i].given => Err(EditError::Given { pos }),
+            Some(i) if self.placements[i].value == value => Ok(()),
+            Some(i) => self.or_restore(|board| {
+                board.retract(i)?;
+                board.place(pos, value, false)
+            }),
+            // a failed placement pops its own moves
+            None => self.place(pos, value, false),
+        }
+    }
+
+    /// Remove the entry of the cell, if any
+    ///
+    /// On failure, the board is left unchanged.
+    pub fn clear(&mut self, pos: Pos) -> Result<(), EditError> {
+        match self.placement(pos) {
+            Some(i) if self.placements[i].given => Err(EditError::Given { pos }),
+            Some(i) => self.or_restore(|board| board.retract(i)),
+            None => Ok(()),
+        }
+    }
+
+    /// Remove every entry, keeping the givens
+    pub fn clear_entries(&mut self) {
+        let first = self.placements.iter().position(|p| !p.given);
+        let Some(first) = first else {
+            return;
+        };
+        // givens are placed first, nothing depends on the entries
+        for placement in self.placements.drain(first..).rev() {
+            self.grid.pop_n_moves(placement.moved);
+            self.placed[placement.pos.index::<N>()] = None;
+        }
+    }
+
+    // Apply an edit made of several placements and retractions, as a whole or not at all
+    fn or_restore(
+        &mut self,
+        edit: impl FnOnce(&mut Self) -> Result<(), EditError>,
+    ) -> Result<(), EditError> {
+        let before = self.clone();
+        edit(self).inspect_err(|_| *self = before)
+    }
+
+    fn placement(&self, pos: Pos) -> Option<usize> {
+        self.placed[pos.index::<N>()]
+    }
+
+    fn place(&mut self, pos: Pos, value: u32, given: bool) -> Result<(), EditError> {
+        if value >= Cell::<N>::R {
+            return Err(EditError::InvalidValue { value });
+        }
+        if let Some(other) = peers::<N>(pos).find(|other| self.value(*other) == Some(value)) {
+            return Err(EditError::Clash { pos, other, value });
+        }
+        let moved = self.grid[pos]
+            .contains(value)
+            .then(|| {
+                self.grid
+                    .remove_all(!Cell::from_value(value), pos, &mut self.defer)
+            })
+            .flatten()
+            .ok_or(EditError::Contradiction { pos, value })?;
+        self.push(Placement {
+            pos,
+            value,
+            given,
+            moved,
+        });
+        Ok(())
+    }
+
+    fn push(&mut self, placement: Placement) {
+        self.placed[placement.pos.index::<N>()] = Some(self.placements.len());
+        self.placements.push(placement);
+    }
+
+    /// Remove the placement at `index`
+    ///
+    /// The moves of the grid form a stack, so the ones of the later
+    /// placements are popped along with it. A later placement only depends
+    /// on it if the cells it settled, or their values in their houses,
+    /// had candidates removed by its moves. The others push their moves
+    /// back as they were, and only the dependent ones are placed again,
+    /// the moves they no longer push or newly push being followed in turn.
+    ///
+    /// Propagation follows the order of the moves, so nothing guarantees
+    /// a dependent placement fits again. If it doesn't, the board is left
+    /// halfway, for the caller to restore.
+    fn retract(&mut self, index: usize) -> Result<(), EditError> {
+        let start = self.placements[..index].iter().map(|p| p.moved).sum();
+        let trail = self.grid.moves_since(start).to_vec();
+        let later = self.placements.split_off(index + 1);
+        let placement = self.placements.pop().unwrap();
+        for p in std::iter::once(&placement).chain(&later) {
+            self.placed[p.pos.index::<N>()] = None;
+        }
+        // the cells each later placement settled, read before its moves are popped
+        let mut settled = Vec::with_capacity(later.len());
+        let mut end = trail.len();
+        for p in later.iter().rev() {
+            let moves = &trail[end - p.moved..end];
+            let cells = moves
+                .iter()
+                .filter_map(|&(_, pos)| Some((self.grid[pos].get_value()?, pos)));
+            settled.push(
+                std::iter::once((p.value, p.pos))
+                    .chain(cells)
+                    .collect::<Vec<_>>(),
+            );
+            self.grid.pop_n_moves(p.moved);
+            end -= p.moved;
+        }
+        settled.reverse();
+        self.grid.pop_n_moves(placement.moved);
+
+        let (moves, mut trail) = trail.split_at(placement.moved);
+        let mut changes = Changes::<N>::new();
+        changes.extend(moves);
+        for (placement, settled) in later.into_iter().zip(settled) {
+            let moves;
+            (moves, trail) = trail.split_at(placement.moved);
+            if !settled
+                .into_iter()
+                .any(|(value, pos)| changes.affects(value, pos))
+            {
+                self.grid.push_moves(moves);
+                self.push(placement);
+                continue;
+            }
+            let before = self.grid.move_count();
+            self.place(placement.pos, placement.value, placement.given)?;
+            // only the moves it no longer pushes, or newly pushes, change
+            let mut old = moves.to_vec();
+            let mut new = self.grid.moves_since(before).to_vec();
+            old.sort_unstable();
+            new.sort_unstable();
+            changes.extend(old.iter().filter(|m| new.binary_search(m).is_err()));
+            changes.extend(new.iter().filter(|m| old.binary_search(m).is_err()));
+        }
+        Ok(())
+    }
+}
+
+// The cells whose candidates may differ from the ones the moves of the
+// placements were pushed on, and the values which may differ in each house
+struct Changes<const N: usize> {
+    cells: Vec<bool>,
+    /// Rows, then columns, then squares, `N*N` values each
+    values: Vec<bool>,
+}
+
+impl<const N: usize> Changes<N> {
+    fn new() -> Self {
+        Self {
+            cells: vec![false; N * N * N * N],
+            values: vec![false; 3 * N * N * N * N],
+        }
+    }
+
+    fn houses(pos: Pos) -> [usize; 3] {
+        let nn = N * N;
+        [
+            pos.row::<N>(),
+            nn + pos.col::<N>(),
+            2 * nn + pos.box_index::<N>(),
+        ]
+    }
+
+    fn extend<'a>(&mut self, moves: impl IntoIterator<Item = &'a (u32, Pos)>) {
+        for &(value, pos) in moves {
+            self.cells[pos.index::<N>()] = true;
+            for house in Self::houses(pos) {
+                self.values[house * N * N + value as usize] = true;
+            }
+        }
+    }
+
+    // A cell settled on `value` was propagated from its candidates, and
+    // from the presence of `value` in its houses: were any of them changed
+    fn affects(&self, value: u32, pos: Pos) -> bool {
+        self.cells[pos.index::<N>()]
+            || Self::houses(pos)
+                .into_iter()
+                .any(|house| self.values[house * N * N + value as usize])
+    }
+}
+
+#[test]
+fn set_and_clear() {
+    let at = |row, col| Pos::from_row_col::<2>(row, col).unwrap();
+    let mut board = Board::<2>::new([(at(0, 0), 0)]).unwrap();
+    assert_eq!(
+        board.set_value(at(0, 0), 1),
+        Err(EditError::Given { pos: at(0, 0) })
+    );
+    assert_eq!(
+        board.set_value(at(0, 3), 0),
+        Err(EditError::Clash {
+            pos: at(0, 3),
+            other: at(0, 0),
+            value: 0
+        })
+    );
+    assert_eq!(
+        board.set_value(at(0, 1), 4),
+        Err(EditError::InvalidValue { value: 4 })
+    );
+
+    board.set_value(at(0, 1), 1).unwrap();
+    board.set_value(at(1, 2), 0).unwrap();
+    board.set_value(at(3, 3), 2).unwrap();
+    let with_all = board.grid().clone();
+
+    // retracting an early entry keeps the later ones
+    board.clear(at(0, 1)).unwrap();
+    assert_eq!(board.value(at(0, 1)), None);
+    assert_eq!(
+        board.entries().collect::<Vec<_>>(),
+        [(at(1, 2), 0), (at(3, 3), 2)]
+    );
+    let rebuilt = Board::<2>::new([(at(0, 0), 0), (at(1, 2), 0), (at(3, 3), 2)]).unwrap();
+    for pos in Pos::iter::<2>() {
+        assert_eq!(board.grid()[pos], rebuilt.grid()[pos]);
+    }
+
+    board.set_value(at(0, 1), 1).unwrap();
+    for pos in Pos::iter::<2>() {
+        assert_eq!(board.grid()[pos], with_all[pos]);
+    }
+
+    board.clear_entries();
+    assert_eq!(board.entries().count(), 0);
+    assert_eq!(board.givens().collect::<Vec<_>>(), [(at(0, 0), 0)]);
+}
+
+#[test]
+fn retract_matches_rebuild() {
+    use rand::seq::SliceRandom;
+    use rand::{RngExt, SeedableRng, rngs::SmallRng};
+
+    let mut rng = SmallRng::seed_from_u64(4);
+    for _ in 0..20 {
+        let full = Sudoku::<3>::generate_shuffled(&mut rng);
+        let mut cells: Vec<Pos> = Pos::iter::<3>().collect();
+        cells.shuffle(&mut rng);
+        let value = |pos: Pos| full[pos].get_value().unwrap();
+        let mut board = Board::<3>::new(cells[..8].iter().map(|&pos| (pos, value(pos)))).unwrap();
+        for &pos in &cells[8..40] {
+            board.set_value(pos, value(pos)).unwrap();
+        }
+        for _ in 0..16 {
+            let entries: Vec<_> = board.entries().collect();
+            let (pos, _) = entries[rng.random_range(0..entries.len())];
+            board.clear(pos).unwrap();
+            let rebuilt = Board::<3>::new(board.givens().chain(board.entries())).unwrap();
+            for pos in Pos::iter::<3>() {
+                assert_eq!(board.grid()[pos], rebuilt.grid()[pos]);
+                assert_eq!(board.value(pos), rebuilt.value(pos));
+            }
+        }
+    }
+}
+
+#[test]
+fn random_edits_match_rebuild() {
+    use rand::seq::IndexedRandom;
+    use rand::{RngExt, SeedableRng, rngs::SmallRng};
+
+    fn assert_same(board: &Board<3>, other: &Board<3>) {
+        for pos in Pos::iter::<3>() {
+            assert_eq!(board.grid()[pos], other.grid()[pos]);
+            assert_eq!(board.value(pos), other.value(pos));
+        }
+    }
+
+    let mut rng = SmallRng::seed_from_u64(7);
+    let (mut refused, mut cleared) = (0, 0);
+    for _ in 0..10 {
+        let full = Sudoku::<3>::generate_shuffled(&mut rng);
+        let givens = Pos::iter::<3>()
+            .filter(|_| rng.random_ratio(1, 4))
+            .map(|pos| (pos, full[pos].get_value().unwrap()));
+        let mut board = Board::<3>::new(givens).unwrap();
+        let cells: Vec<Pos> = Pos::iter::<3>().collect();
+        for _ in 0..100 {
+            // entries are random, wrong ones are refused or ruled out later
+            let before = board.clone();
+            let pos = *cells.choose(&mut rng).unwrap();
+            let result = match rng.random_ratio(1, 3) {
+                true => board.clear(pos),
+                false => board.set_value(pos, rng.random_range(0..9)),
+            };
+            match result {
+                Ok(()) => {
+                    cleared +=
+                        usize::from(before.value(pos).is_some() && board.value(pos).is_none());
+                    let rebuilt = Board::<3>::new(board.givens().chain(board.entries())).unwrap();
+                    assert_same(&board, &rebuilt);
+                }
+                Err(_) => {
+                    refused += 1;
+                    assert_same(&board, &before);
+                    assert!(board.entries().eq(before.entries()));
+                }
+            }
+        }
+    }
+    assert!(refused > 0 && cleared > 0);
+}
+
+#[test]
+fn failed_edit_keeps_previous_entry() {
+    let at = |row, col| Pos::from_row_col::<2>(row, col).unwrap();
+    let mut board = Board::<2>::new([(at(0, 0), 0)]).unwrap();
+    board.set_value(at(1, 1), 1).unwrap();
+    assert!(board.set_value(at(1, 1), 0).is_err());
+    assert_eq!(board.value(at(1, 1)), Some(1));
+}
+
+#[test]
+fn styled_print() {
+    let at = |row, col| Pos::from_row_col::<2>(row, col).unwrap();
+    let mut board = Board::<2>::new([(at(0, 0), 0)]).unwrap();
+    board.set_value(at(0, 1), 1).unwrap();
+    let style = Style {
+        colors: true,
+        ascii: true,
+    };
+    let mut printed = Vec::new();
+    board
+        .print(&mut printed, &Alphabet::standard(), style)
+        .unwrap();
+    let printed = String::from_utf8(printed).unwrap();
+    let lines: Vec<&str> = printed.lines().collect();
+    assert_eq!(lines[0], "+===+===+===+===+");
+    assert_eq!(lines[1], "| \x1b[1m1\x1b[0m : \x1b[32m2\x1b[0m |   :   |");
+    assert_eq!(lines[2], "+---+---+---+---+");
+    assert!(printed.is_ascii());
+
+    // the propagated values are not entries, and are not shown
+    let mut plain = Vec::new();
+    board
+        .print(&mut plain, &Alphabet::standard(), Style::plain())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(plain)
+            .unwrap()
+            .matches(['1', '2', '3', '4'])
+            .count(),
+        2
+    );
+}
@@ -0,0 +1,254 @@
+use std::cmp::Ordering;
+
+use crate::transform::identity;
+use crate::{Pos, Size, Storage, Sudoku, Transform};
+
+// Every permutation of `0..N`
+fn permutations<const N: usize>() -> Vec<[u8; N]> {
+    fn permute<const N: usize>(perm: &mut [u8; N], k: usize, all: &mut Vec<[u8; N]>) {
+        if k == N {
+            all.push(*perm);
+            return;
+        }
+        for i in k..N {
+            perm.swap(k, i);
+            permute(perm, k + 1, all);
+            perm.swap(k, i);
+        }
+    }
+    let mut all = Vec::new();
+    permute(&mut identity(), 0, &mut all);
+    all
+}
+
+// The smallest form found so far, row by row, `u32::MAX` past the known rows
+struct Best<const N: usize> {
+    form: Vec<u32>,
+    transform: Option<Transform<N>>,
+}
+
+// The rows of the grid being ordered, with its columns already placed
+struct Search<'a, const N: usize> {
+    transpose: bool,
+    /// 0 for blanks, the value plus one otherwise, in reading order
+    cells: &'a [u32],
+    /// The input column of each output column
+    columns: Vec<usize>,
+    /// The input row of each output row
+    rows: Vec<usize>,
+    used: Vec<bool>,
+    /// The label of each value plus one, 0 when not seen yet
+    labels: Vec<u32>,
+    next: u32,
+    form: Vec<u32>,
+}
+
+impl<const N: usize> Search<'_, N> {
+    const R: usize = N * N;
+
+    fn run(&mut self, best: &mut Best<N>) {
+        let depth = self.rows.len();
+        if depth == Self::R {
+            if best.transform.is_none() {
+                best.transform = Some(self.transform());
+            }
+            return;
+        }
+        let candidates = match depth % N {
+            0 => 0..Self::R,
+            _ => {
+                let band = self.rows[depth - depth % N] / N;
+                band * N..(band + 1) * N
+            }
+        };
+        let line = depth * Self::R..(depth + 1) * Self::R;
+        for row in candidates {
+            // a sibling may have found a smaller prefix meanwhile
+            if depth > 0
+                && self.form[line.start - Self::R..line.start]
+                    != best.form[line.start - Self::R..line.start]
+            {
+                return;
+            }
+            if self.used[row] {
+                continue;
+            }
+            let before = self.next;
+            for col in 0..Self::R {
+                let value = self.cells[row * Self::R + self.columns[col]];
+                if value != 0 && self.labels[value as usize] == 0 {
+                    self.next += 1;
+                    self.labels[value as usize] = self.next;
+                }
+                self.form[line.start + col] = self.labels[value as usize];
+            }
+            match self.form[line.clone()].cmp(&best.form[line.clone()]) {
+                Ordering::Greater => {}
+                ordering => {
+                    if ordering == Ordering::Less {
+                        best.form[line.clone()].copy_from_slice(&self.form[line.clone()]);
+                        best.form[line.end..].fill(u32::MAX);
+                        best.transform = None;
+                    }
+                    self.used[row] = true;
+                    self.rows.push(row);
+                    self.run(best);
+                    self.rows.pop();
+                    self.used[row] = false;
+                }
+            }
+            for col in 0..Self::R {
+                let value = self.cells[row * Self::R + self.columns[col]] as usize;
+                if self.labels[value] > before {
+                    self.labels[value] = 0;
+                }
+            }
+            self.next = before;
+        }
+    }
+
+    fn transform(&self) -> Transform<N> {
+        let mut transform = Transform::identity();
+        transform.transpose = self.transpose;
+        for (to, &from) in self.rows.iter().enumerate() {
+            transform.bands[from / N] = (to / N) as u8;
+            transform.rows[from / N][from % N] = (to % N) as u8;
+        }
+        for (to, &from) in self.columns.iter().enumerate() {
+            transform.stacks[from / N] = (to / N) as u8;
+            transform.columns[from / N][from % N] = (to % N) as u8;
+        }
+        // the values absent from the grid take the last labels, in order
+        let mut next = self.next;
+        for value in 0..Self::R {
+            transform.values[value] = match self.labels[value + 1] {
+                0 => {
+                    next += 1;
+                    next - 1
+                }
+                label => label - 1,
+            };
+        }
+        transform
+    }
+}
+
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    /// The largest size [Sudoku::canonical] searches, 9×9 grids
+    pub const CANONICAL_MAX: usize = 3;
+
+    /// The representative of the grid among all its isomorphic copies,
+    /// with the transform leading to it
+    ///
+    /// Two grids have the same canonical form if and only if one is a
+    /// [Transform] of the other. Only the determined values are compared:
+    /// the other candidates are merely carried along.
+    ///
+    /// The search goes over every arrangement of the columns, which makes it
+    /// practical up to 9×9 grids only: `None` for larger ones.
+    pub fn canonical(&self) -> Option<(Self, Transform<N>)> {
+        if N > Self::CANONICAL_MAX {
+            return None;
+        }
+        let r = N * N;
+        let straight: Vec<u32> = Pos::iter::<N>()
+            .map(|pos| self[pos].get_value().map_or(0, |value| value + 1))
+            .collect();
+        let transposed: Vec<u32> = (0..r * r).map(|i| straight[(i % r) * r + i / r]).collect();
+
+        let mut best = Best {
+            form: vec![u32::MAX; r * r],
+            transform: None,
+        };
+        let permutations = permutations::<N>();
+        for (transpose, cells) in [(false, &straight), (true, &transposed)] {
+            for stacks in &permutations {
+                // one permutation of the columns of each stack, in mixed radix
+                for mut index in 0..permutations.len().pow(N as u32) {
+                    let mut columns = Vec::with_capacity(r);
+                    for &stack in stacks {
+                        let within = &permutations[index % permutations.len()];
+                        index /= permutations.len();
+                        columns.extend(within.iter().map(|&col| stack as usize * N + col as usize));
+                    }
+                    Search {
+                        transpose,
+                        cells,
+                        columns,
+                        rows: Vec::with_capacity(r),
+                        used: vec![false; r],
+                        labels: vec![0; r + 1],
+                        next: 0,
+                        form: vec![0; r * r],
+                    }
+                    .run(&mut best);
+                }
+            }
+        }
+        let transform = best.transform.expect("some arrangement is the smallest");
+        Some((transform.apply(self), transform))
+    }
+}
+
+#[cfg(test)]
+fn same_form<const N: usize>(a: &Sudoku<N>, b: &Sudoku<N>) -> bool
+where
+    Size<N>: Storage,
+{
+    Pos::iter::<N>().all(|pos| a[pos].get_value() == b[pos].get_value())
+}
+
+#[test]
+fn isomorphic_copies() {
+    let grid = Sudoku::<2>::from_numbers("1 0 0 0\n0 0 3 0\n0 4 0 0\n0 0 0 0").unwrap();
+    let (canonical, transform) = grid.canonical().unwrap();
+    assert!(same_form(&canonical, &transform.apply(&grid)));
+    assert!(same_form(&canonical.canonical().unwrap().0, &canonical));
+
+    let mut copy = Transform::<2>::identity();
+    copy.transpose = true;
+    copy.bands = [1, 0];
+    copy.rows = [[1, 0], [0, 1]];
+    copy.columns = [[0, 1], [1, 0]];
+    copy.values = vec![2, 0, 3, 1];
+    let other = copy.apply(&grid);
+    assert!(!same_form(&other, &grid));
+    assert!(same_form(&other.canonical().unwrap().0, &canonical));
+
+    let different = Sudoku::<2>::from_numbers("1 2 0 0\n0 0 0 0\n0 0 0 0\n0 0 0 0").unwrap();
+    assert!(!same_form(&different.canonical().unwrap().0, &canonical));
+    assert!(Sudoku::<4>::default().canonical().is_none());
+}
+
+#[test]
+fn canonical_9x9() {
+    let grid = Sudoku::<3>::from_numbers(
+        "5 3 0 0 7 0 0 0 0
+         6 0 0 1 9 5 0 0 0
+         0 9 8 0 0 0 0 6 0
+         8 0 0 0 6 0 0 0 3
+         4 0 0 8 0 3 0 0 1
+         7 0 0 0 2 0 0 0 6
+         0 6 0 0 0 0 2 8 0
+         0 0 0 4 1 9 0 0 5
+         0 0 0 0 8 0 0 7 9",
+    )
+    .unwrap();
+    let (canonical, transform) = grid.canonical().unwrap();
+    assert!(same_form(&canonical, &transform.apply(&grid)));
+
+    let mut copy = Transform::<3>::identity();
+    copy.transpose = true;
+    copy.bands = [2, 0, 1];
+    copy.rows[1] = [2, 1, 0];
+    copy.stacks = [1, 2, 0];
+    copy.columns[0] = [1, 0, 2];
+    copy.values = vec![4, 8, 0, 6, 1, 3, 5, 2, 7];
+    assert!(same_form(
+        &copy.apply(&grid).canonical().unwrap().0,
+        &canonical
+    ));
+}
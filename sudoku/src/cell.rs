@@ -1,17 +1,25 @@
 use rand::{Rng, RngExt};
+
+use crate::{Bits, Size, Storage};
 use std::ops::{BitAnd, BitOr, BitOrAssign, Not, Sub};
 
 /// Represents the content of one cell of the grid
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Cell<const N: usize> {
+pub struct Cell<const N: usize>
+where
+    Size<N>: Storage,
+{
     /// The bitset for all possible values
     ///
     /// `1` means could contain
     /// `0` means can't contain
-    bitset: u64,
+    bitset: <Size<N> as Storage>::Bits,
 }
 
-impl<const N: usize> Default for Cell<N> {
+impl<const N: usize> Default for Cell<N>
+where
+    Size<N>: Storage,
+{
     fn default() -> Self {
         Self::EMPTY
     }
@@ -20,19 +28,21 @@ impl<const N: usize> Default for Cell<N> {
 // This is an implementation block.
 // It contains all associated constants and methods to Cell.
 /// `R` is the range of values, i.e. the MAX+1
-impl<const N: usize> Cell<N> {
+impl<const N: usize> Cell<N>
+where
+    Size<N>: Storage,
+{
     pub const R: u32 = (N * N) as u32;
 
     /// No possible number in that cell
-    pub const EMPTY: Self = Self { bitset: 0 };
+    pub const EMPTY: Self = Self { bitset: Bits::ZERO };
 
     /// All possible number in that cell
     pub const FULL: Self = Self {
-        bitset: !(!0u64).unbounded_shl(Self::R),
-        // bitset: !(!0 << Self::R),
+        bitset: <Size<N> as Storage>::FULL,
     };
 
-    pub const fn bitset(self) -> u64 {
+    pub fn bitset(self) -> <Size<N> as Storage>::Bits {
         self.bitset
     }
 
@@ -40,16 +50,18 @@ impl<const N: usize> Cell<N> {
     /// Only one specific value in that cell
     #[inline]
     #[must_use]
-    pub const fn from_value(value: u32) -> Self {
+    pub fn from_value(value: u32) -> Self {
         debug_assert!(value < Self::R);
-        Self { bitset: 1 << value }
+        Self {
+            bitset: Bits::bit(value),
+        }
     }
 
     /// If one and exactly one value, return it
     #[inline]
     #[must_use]
-    pub const fn get_value(self) -> Option<u32> {
-        if self.bitset.is_power_of_two() {
+    pub fn get_value(self) -> Option<u32> {
+        if self.bitset.count_ones() == 1 {
             Some(self.bitset.trailing_zeros())
         } else {
             None
@@ -81,13 +93,13 @@ impl<const N: usize> Cell<N> {
                 // choose last one
                 0 => Some(self.bitset.trailing_zeros()),
                 // choose first one
-                1 => Some(63 - self.bitset.leading_zeros()),
+                1 => Some(<Size<N> as Storage>::Bits::BITS - 1 - self.bitset.leading_zeros()),
                 n => {
                     // iterate through n values
                     let mut bitset = self.bitset;
                     for _ in 0..n - 1 {
                         let value = bitset.trailing_zeros();
-                        bitset = bitset & !(1 << value);
+                        bitset &= !Self::from_value(value).bitset;
                     }
                     Some(bitset.trailing_zeros())
                 }
@@ -98,105 +110,40 @@ impl<const N: usize> Cell<N> {
     /// Is `value` one of the possiblities
     #[inline]
     #[must_use]
-    pub const fn contains(self, value: u32) -> bool {
+    pub fn contains(self, value: u32) -> bool {
         debug_assert!(value < Self::R);
-        self.bitset & (1 << value) != 0
+        self.bitset & Bits::bit(value) != Bits::ZERO
     }
 
     /// Remove if present, the `value` possiblity
     #[inline]
-    pub const fn remove(&mut self, value: u32) {
+    pub fn remove(&mut self, value: u32) {
         debug_assert!(value < Self::R);
         debug_assert!(self.contains(value));
-        self.bitset &= !(1 << value);
+        self.bitset &= !Self::from_value(value).bitset;
         // debug_assert!(self.len() > 0);
     }
 
     /// How many possibilities
     #[inline]
     #[must_use]
-    pub const fn len(self) -> usize {
+    pub fn len(self) -> usize {
         self.bitset.count_ones() as usize
     }
 
-    pub const fn from_char(c: char) -> Option<Self> {
-        Some(Self::from_value(match c {
-            '1' => 0,
-            '2' => 1,
-            '3' => 2,
-            '4' => 3,
-            '5' => 4,
-            '6' => 5,
-            '7' => 6,
-            '8' => 7,
-            '9' => 8,
-            'A' => 9,
-            'B' => 10,
-            'C' => 11,
-            'D' => 12,
-            'E' => 13,
-            'F' => 14,
-            'G' => 15,
-            'H' => 16,
-            'I' => 17,
-            'J' => 18,
-            'K' => 19,
-            'L' => 20,
-            'M' => 21,
-            'N' => 22,
-            'O' => 23,
-            'P' => 24,
-            'Q' => 25,
-            'R' => 26,
-            'S' => 27,
-            'T' => 28,
-            'U' => 29,
-            'V' => 30,
-            'W' => 31,
-            'X' => 32,
-            'Y' => 33,
-            'Z' => 34,
-            '0' => 35,
-            'Ψ' => 36,
-            'Ω' => 37,
-            'Φ' => 38,
-            'Δ' => 39,
-            'Ξ' => 40,
-            'Γ' => 41,
-            'Π' => 42,
-            'Σ' => 43,
-            'Д' => 44,
-            'Б' => 45,
-            'Џ' => 46,
-            'Ш' => 47,
-            'Ч' => 48,
-            'ก' => 49,
-            'ข' => 50,
-            'ค' => 51,
-            'ฉ' => 52,
-            'ช' => 53,
-            'ง' => 54,
-            'ด' => 55,
-            'ฮ' => 56,
-            'ล' => 57,
-            'ห' => 58,
-            'น' => 59,
-            'ฯ' => 60,
-            'ร' => 61,
-            'ฆ' => 62,
-            'พ' => 63,
-            '_' => {
-                return Some(Self::FULL);
-            }
-            _ => {
-                return None;
-            }
-        }))
+    /// No possibility at all
+    #[inline]
+    #[must_use]
+    pub fn is_empty(self) -> bool {
+        self.bitset == Bits::ZERO
     }
 }
 
 // Implement the bitwise OR operation (|)
-impl<const N: usize> BitOr for Cell<N> {
+impl<const N: usize> BitOr for Cell<N>
+where
+    Size<N>: Storage,
+{
     type Output = Self;
 
     fn bitor(self, rhs: Self) -> Self::Output {
@@ -207,14 +154,20 @@ impl<const N: usize> BitOr for Cell<N> {
 }
 
 // Implement the bitwise OR operation for assignation (|=)
-impl<const N: usize> BitOrAssign for Cell<N> {
+impl<const N: usize> BitOrAssign for Cell<N>
+where
+    Size<N>: Storage,
+{
     fn bitor_assign(&mut self, rhs: Self) {
         *self = *self | rhs;
     }
 }
 
 // Implement the bitwise AND operation (&)
-impl<const N: usize> BitAnd for Cell<N> {
+impl<const N: usize> BitAnd for Cell<N>
+where
+    Size<N>: Storage,
+{
     type Output = Self;
 
     fn bitand(self, rhs: Self) -> Self::Output {
@@ -225,7 +178,10 @@ impl<const N: usize> BitAnd for Cell<N> {
 }
 
 // Implement the bitwise NOT operation (!)
-impl<const N: usize> Not for Cell<N> {
+impl<const N: usize> Not for Cell<N>
+where
+    Size<N>: Storage,
+{
     type Output = Self;
 
     fn not(self) -> Self::Output {
@@ -236,20 +192,26 @@ impl<const N: usize> Not for Cell<N> {
 }
 
 // We can iterate on the possible values of a cell
-impl<const R: usize> Iterator for Cell<R> {
+impl<const N: usize> Iterator for Cell<N>
+where
+    Size<N>: Storage,
+{
     type Item = u32;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.bitset == 0 {
+        if self.bitset == Bits::ZERO {
             return None;
         }
         let value = self.bitset.trailing_zeros();
-        self.bitset = self.bitset & !(1 << value);
+        self.bitset &= !Self::from_value(value).bitset;
         Some(value)
     }
 }
 
-impl<const N: usize> Sub<u32> for Cell<N> {
+impl<const N: usize> Sub<u32> for Cell<N>
+where
+    Size<N>: Storage,
+{
     type Output = Self;
 
     fn sub(self, rhs: u32) -> Self::Output {
@@ -266,7 +228,7 @@ fn test_pop_random() {
     assert_eq!(full.len(), 25);
     assert_eq!(empty.len(), 0);
     let mut rng = SmallRng::from_seed([145; 32]);
-    while full.len() > 0 {
+    while !full.is_empty() {
         let value = full.choose(&mut rng).unwrap();
         full.remove(value);
         assert!(!empty.contains(value));
@@ -0,0 +1,285 @@
+use crate::{AnySudoku, LoadingError};
+
+/// What a puzzle author needs to know about a puzzle
+#[derive(Debug, Clone)]
+pub struct Check {
+    /// Each pair of givens holding the same value in a house, as
+    /// [LoadingError::Conflicting], or else the given ruled out by
+    /// the others, as [LoadingError::Contradiction]
+    pub conflicts: Vec<LoadingError>,
+    /// The number of solutions, at most the `max_solutions` of [Check::new]
+    pub solutions: usize,
+    /// The search ran out of steps before counting them all,
+    /// `solutions` is only a lower bound
+    pub exhausted: bool,
+    /// The first solution found, if any
+    pub solution: Option<AnySudoku>,
+    /// The givens, as rows and columns, whose removal keeps the solution unique,
+    /// only searched for by [Check::new] when there is a single solution
+    pub redundant: Vec<(usize, usize)>,
+    /// The givens whose removal couldn't be decided within the steps
+    pub undecided: Vec<(usize, usize)>,
+}
+
+impl Check {
+    /// The default number of steps each search may take,
+    /// as the time to live of [AnySudoku::solutions]
+    pub const STEPS: usize = 1 << 20;
+
+    /// Check the givens, one value per cell in the order of [Pos::iter](crate::Pos::iter)
+    ///
+    /// Solutions are counted up to `max_solutions`, and at least two,
+    /// which tells a unique solution apart.
+    /// It fails only if the values don't make a grid, see [AnySudoku::from_values].
+    pub fn new(values: &[Option<u32>], max_solutions: usize) -> Result<Self, LoadingError> {
+        Self::with_steps(values, max_solutions, Self::STEPS)
+    }
+
+    /// Same as [Check::new], each search taking at most `steps` steps
+    pub fn with_steps(
+        values: &[Option<u32>],
+        max_solutions: usize,
+        steps: usize,
+    ) -> Result<Self, LoadingError> {
+        let mut check = Self::count_with_steps(values, max_solutions, steps)?;
+        if check.is_unique() {
+            let nn = AnySudoku::size_of_len(values.len())
+                .unwrap_or_default()
+                .pow(2);
+            check.find_redundant(values, nn, steps);
+        }
+        Ok(check)
+    }
+
+    /// Same as [Check::new], without looking for redundant givens,
+    /// which takes one more search per given
+    pub fn count(values: &[Option<u32>], max_solutions: usize) -> Result<Self, LoadingError> {
+        Self::count_with_steps(values, max_solutions, Self::STEPS)
+    }
+
+    fn count_with_steps(
+        values: &[Option<u32>],
+        max_solutions: usize,
+        steps: usize,
+    ) -> Result<Self, LoadingError> {
+        let received = values.len();
+        let n = AnySudoku::size_of_len(received).ok_or(LoadingError::InvalidSize { received })?;
+        let nn = n * n;
+        if let Some(i) = values
+            .iter()
+            .position(|value| value.is_some_and(|v| v >= nn as u32))
+        {
+            let value = values[i].unwrap_or_default();
+            return Err(LoadingError::InvalidValue {
+                value,
+                row: i / nn,
+                col: i % nn,
+            });
+        }
+
+        let mut check = Self {
+            conflicts: conflicts(n, values),
+            solutions: 0,
+            exhausted: false,
+            solution: None,
+            redundant: Vec::new(),
+            undecided: Vec::new(),
+        };
+        if !check.conflicts.is_empty() {
+            return Ok(check);
+        }
+        let mut grid = match AnySudoku::from_values(values) {
+            Ok(grid) => grid,
+            Err(err) => {
+                check.conflicts.push(err);
+                return Ok(check);
+            }
+        };
+        (check.solution, check.solutions, check.exhausted) =
+            search(&mut grid, max_solutions.max(2), steps);
+        Ok(check)
+    }
+
+    // The givens without which the solution is still unique,
+    // and the ones for which the search ran out of steps
+    fn find_redundant(&mut self, values: &[Option<u32>], nn: usize, steps: usize) {
+        let mut values = values.to_vec();
+        for i in 0..values.len() {
+            let Some(value) = values[i].take() else {
+                continue;
+            };
+            let mut grid = AnySudoku::from_values(&values).expect("fewer givens can't conflict");
+            match search(&mut grid, 2, steps) {
+                (_, _, true) => self.undecided.push((i / nn, i % nn)),
+                (_, 1, false) => self.redundant.push((i / nn, i % nn)),
+                _ => {}
+            }
+            values[i] = Some(value);
+        }
+    }
+
+    /// Whether the puzzle has a single solution
+    pub fn is_unique(&self) -> bool {
+        self.solutions == 1 && !self.exhausted
+    }
+
+    /// Whether the solution is unique and every given is needed for it,
+    /// always `true` for a unique solution after [Check::count]
+    pub fn is_minimal(&self) -> bool {
+        self.is_unique() && self.redundant.is_empty() && self.undecided.is_empty()
+    }
+}
+
+// The first solution, the number of solutions up to `max`,
+// and whether the search ran out of steps before reaching it
+fn search(grid: &mut AnySudoku, max: usize, steps: usize) -> (Option<AnySudoku>, usize, bool) {
+    let taken = std::cell::Cell::new(0);
+    let ttl = (0..steps).inspect(|_| taken.set(taken.get() + 1));
+    let mut solutions = grid.solutions((), ttl, 0);
+    let first = solutions.next();
+    let count = first.is_some() as usize + solutions.take(max - 1).count();
+    let exhausted = count < max && taken.get() == steps;
+    (first, count, exhausted)
+}
+
+// Every pair of equal givens sharing a row, a column or a square
+//
+// Each given is only compared with the earlier cells of its row,
+// its column and its square.
+fn conflicts(n: usize, values: &[Option<u32>]) -> Vec<LoadingError> {
+    let nn = n * n;
+    let mut conflicts = Vec::new();
+    let mut peers = Vec::with_capacity(3 * nn);
+    for (second, value) in values.iter().enumerate() {
+        let Some(value) = *value else {
+            continue;
+        };
+        let (row, col) = (second / nn, second % nn);
+        let (top, left) = (row - row % n, col - col % n);
+        peers.clear();
+        peers.extend((0..col).map(|c| row * nn + c));
+        peers.extend((0..row).map(|r| r * nn + col));
+        peers.extend(
+            (top..=row)
+                .flat_map(|r| (left..left + n).map(move |c| r * nn + c))
+                .filter(|first| *first < second),
+        );
+        peers.sort_unstable();
+        peers.dedup();
+        for &first in &peers {
+            if values[first] == Some(value) {
+                conflicts.push(LoadingError::Conflicting {
+                    value,
+                    first: (first / nn, first % nn),
+                    second: (row, col),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+#[test]
+fn check_puzzles() {
+    use crate::Alphabet;
+
+    let puzzle =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+    let alphabet = Alphabet::standard();
+    let values = |line: &str| crate::Layout::Line.read(line, &alphabet).unwrap();
+
+    let check = Check::new(&values(puzzle), 3).unwrap();
+    assert!(check.conflicts.is_empty());
+    assert!(check.is_unique());
+    assert!(check.is_minimal());
+
+    let mut extra = values(puzzle);
+    extra[1] = check.solution.as_ref().unwrap().values()[1];
+    let check = Check::new(&extra, 3).unwrap();
+    assert!(check.is_unique());
+    assert_eq!(check.redundant, [(0, 1)]);
+
+    let check = Check::count(&extra, 3).unwrap();
+    assert!(check.is_unique());
+    assert!(check.redundant.is_empty());
+
+    let check = Check::new(&values(&puzzle.replace("4.....8.5", ".........")), 3).unwrap();
+    assert_eq!(check.solutions, 3);
+    assert!(check.redundant.is_empty());
+
+    // a second solution is always looked for
+    let check = Check::new(&values(&puzzle.replace("4.....8.5", ".........")), 1).unwrap();
+    assert_eq!(check.solutions, 2);
+    assert!(!check.is_unique());
+
+    // out of steps, nothing is claimed
+    let check = Check::with_steps(&values(puzzle), 3, 10).unwrap();
+    assert!(check.exhausted);
+    assert!(!check.is_unique() && !check.is_minimal());
+
+    let check = Check::new(&values(&puzzle.replacen("4.....8", "4.4...4", 1)), 3).unwrap();
+    assert_eq!(check.solutions, 0);
+    assert_eq!(check.conflicts.len(), 5);
+    assert_eq!(
+        check.conflicts[..2],
+        [
+            LoadingError::Conflicting {
+                value: 3,
+                first: (0, 0),
+                second: (0, 2),
+            },
+            LoadingError::Conflicting {
+                value: 3,
+                first: (0, 0),
+                second: (0, 6),
+            },
+        ]
+    );
+    assert!(check.conflicts.contains(&LoadingError::Conflicting {
+        value: 3,
+        first: (0, 6),
+        second: (4, 6),
+    }));
+}
+
+#[test]
+fn conflicts_only_among_peers() {
+    use rand::{RngExt, SeedableRng, rngs::SmallRng};
+
+    // every pair of cells, in the order of the second then the first
+    fn all_pairs(n: usize, values: &[Option<u32>]) -> Vec<LoadingError> {
+        let nn = n * n;
+        let mut conflicts = Vec::new();
+        for (second, value) in values.iter().enumerate() {
+            let (row, col) = (second / nn, second % nn);
+            for (first, other) in values[..second].iter().enumerate() {
+                let (first_row, first_col) = (first / nn, first % nn);
+                let shared = first_row == row
+                    || first_col == col
+                    || (first_row / n, first_col / n) == (row / n, col / n);
+                if let Some(value) = *value
+                    && shared
+                    && *other == Some(value)
+                {
+                    conflicts.push(LoadingError::Conflicting {
+                        value,
+                        first: (first_row, first_col),
+                        second: (row, col),
+                    });
+                }
+            }
+        }
+        conflicts
+    }
+
+    let mut rng = SmallRng::seed_from_u64(0);
+    for n in 2..=4 {
+        let values: Vec<_> = (0..n * n * n * n)
+            .map(|_| {
+                rng.random_bool(0.3)
+                    .then(|| rng.random_range(0..n as u32 * n as u32))
+            })
+            .collect();
+        assert_eq!(conflicts(n, &values), all_pairs(n, &values));
+    }
+}
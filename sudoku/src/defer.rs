@@ -1,5 +1,6 @@
 use super::Pos;
 
+#[derive(Debug, Clone)]
 pub struct Defer<const N: usize> {
     grid: [[[[bool; N]; N]; N]; N],
     queue: Vec<Pos>,
@@ -28,3 +29,8 @@ impl<const N: usize> Defer<N> {
         self.queue.clear();
     }
 }
+impl<const N: usize> Default for Defer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
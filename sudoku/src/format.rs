@@ -0,0 +1,699 @@
+use std::io::Write;
+
+use crate::{Alphabet, AnySudoku, LoadingError, Pos, Size, Storage, Sudoku};
+
+// A character of the text, lines and columns counted from 1
+#[derive(Debug, Clone, Copy)]
+struct Located {
+    char: char,
+    line: usize,
+    column: usize,
+}
+
+fn located_line(index: usize, text: &str) -> impl Iterator<Item = Located> + '_ {
+    text.chars().enumerate().map(move |(column, char)| Located {
+        char,
+        line: index + 1,
+        column: column + 1,
+    })
+}
+
+fn located(src: &str) -> impl Iterator<Item = Located> + '_ {
+    src.lines()
+        .enumerate()
+        .flat_map(|(index, text)| located_line(index, text))
+}
+
+// One cell per character, whitespace is skipped
+struct Cells {
+    values: Vec<Option<u32>>,
+    chars: Vec<Located>,
+}
+
+impl Cells {
+    // Only `_` is a blank when strict, otherwise `.` is too, and so is `0`
+    // when it is not one of the symbols of the grid
+    fn read(
+        chars: impl Iterator<Item = Located>,
+        strict: bool,
+        alphabet: &Alphabet,
+    ) -> Result<Self, LoadingError> {
+        let chars: Vec<Located> = chars.filter(|c| !c.char.is_whitespace()).collect();
+        let size = AnySudoku::size_of_len(chars.len());
+        let zero_blank = !strict
+            && alphabet
+                .value('0')
+                .is_none_or(|value| size.is_some_and(|n| value >= (n * n) as u32));
+        let values = chars
+            .iter()
+            .map(|&Located { char, line, column }| match char {
+                '_' => Ok(None),
+                '.' if !strict => Ok(None),
+                '0' if zero_blank => Ok(None),
+                char => alphabet
+                    .value(char)
+                    .map(Some)
+                    .ok_or(LoadingError::InvalidCharacter { char, line, column }),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { values, chars })
+    }
+
+    // The values, if they all fit in a grid of size `n`
+    fn values(&self, n: usize) -> Result<&[Option<u32>], LoadingError> {
+        let received = self.values.len();
+        if received != n * n * n * n {
+            return Err(LoadingError::InvalidSize { received });
+        }
+        let invalid = self
+            .values
+            .iter()
+            .position(|value| value.is_some_and(|value| value >= (n * n) as u32));
+        if let Some(i) = invalid {
+            let Located { char, line, column } = self.chars[i];
+            return Err(LoadingError::InvalidCharacter { char, line, column });
+        }
+        Ok(&self.values)
+    }
+
+    fn load<const N: usize>(&self) -> Result<Sudoku<N>, LoadingError>
+    where
+        Size<N>: Storage,
+    {
+        Sudoku::from_values(self.values(N)?)
+    }
+
+    fn load_any(&self) -> Result<AnySudoku, LoadingError> {
+        let received = self.values.len();
+        let size =
+            AnySudoku::size_of_len(received).ok_or(LoadingError::InvalidSize { received })?;
+        AnySudoku::from_values(self.values(size)?)
+    }
+}
+
+fn line_cells(line: &str, alphabet: &Alphabet) -> Result<Cells, LoadingError> {
+    Cells::read(located(line), false, alphabet)
+}
+
+// `#` lines are metadata, an optional `[Puzzle]` header comes first,
+// and any following section, like `[State]`, is ignored
+fn sdk_cells(src: &str, alphabet: &Alphabet) -> Result<Cells, LoadingError> {
+    let chars = src
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim_start().starts_with('#'))
+        .skip_while(|(_, text)| text.trim().is_empty() || text.trim() == "[Puzzle]")
+        .take_while(|(_, text)| !text.trim_start().starts_with('['))
+        .flat_map(|(index, text)| located_line(index, text));
+    Cells::read(chars, false, alphabet)
+}
+
+// Squares are separated by `|` and lines of `-`
+fn ss_cells(src: &str, alphabet: &Alphabet) -> Result<Cells, LoadingError> {
+    let chars = src
+        .lines()
+        .enumerate()
+        .filter(|(_, text)| !text.trim_start().starts_with('-'))
+        .flat_map(|(index, text)| located_line(index, text))
+        .filter(|c| c.char != '|');
+    Cells::read(chars, false, alphabet)
+}
+
+// The puzzle of each line of a collection: the first word, before any `#` comment
+fn collection_cells<'a>(
+    src: &'a str,
+    alphabet: &'a Alphabet,
+) -> impl Iterator<Item = Result<Cells, LoadingError>> + 'a {
+    src.lines()
+        .enumerate()
+        .map(|(index, text)| {
+            located_line(index, text)
+                .skip_while(|c| c.char.is_whitespace())
+                .take_while(|c| !c.char.is_whitespace() && c.char != '#')
+                .collect::<Vec<_>>()
+        })
+        .filter(|chars| !chars.is_empty())
+        .map(move |chars| Cells::read(chars.into_iter(), false, alphabet))
+}
+
+// Whitespace or commas separate the numbers, `0`, `.` and `_` are blanks
+fn number_cells(src: &str) -> Result<Cells, LoadingError> {
+    let tokens: Vec<Vec<Located>> = src
+        .lines()
+        .enumerate()
+        .flat_map(|(index, text)| {
+            let chars: Vec<Located> = located_line(index, text).collect();
+            chars
+                .split(|c| c.char.is_whitespace() || c.char == ',')
+                .filter(|token| !token.is_empty())
+                .map(<[Located]>::to_vec)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let size = AnySudoku::size_of_len(tokens.len());
+    let mut cells = Cells {
+        values: Vec::with_capacity(tokens.len()),
+        chars: Vec::with_capacity(tokens.len()),
+    };
+    for token in tokens {
+        let number: String = token.iter().map(|c| c.char).collect();
+        let Located { line, column, .. } = token[0];
+        let value = match number.as_str() {
+            "0" | "." | "_" => None,
+            _ => {
+                if let Some(&Located { char, line, column }) =
+                    token.iter().find(|c| !c.char.is_ascii_digit())
+                {
+                    return Err(LoadingError::InvalidCharacter { char, line, column });
+                }
+                let value = number
+                    .parse::<u32>()
+                    .ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .filter(|value| size.is_none_or(|n| *value < (n * n) as u32));
+                match value {
+                    Some(value) => Some(value),
+                    None => {
+                        return Err(LoadingError::InvalidNumber {
+                            number,
+                            line,
+                            column,
+                        });
+                    }
+                }
+            }
+        };
+        cells.values.push(value);
+        cells.chars.push(token[0]);
+    }
+    Ok(cells)
+}
+
+/// How the cells of a puzzle are written, see the loaders of [Sudoku]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// See [Sudoku::from_str_strict]
+    Strict,
+    /// The first puzzle of a collection, see [Sudoku::from_collection]
+    Line,
+    /// See [Sudoku::from_sdk]
+    Sdk,
+    /// See [Sudoku::from_ss]
+    Ss,
+    /// See [Sudoku::from_numbers]
+    Numbers,
+}
+
+impl Layout {
+    /// The values of the cells, in the order of [Pos::iter], as they are written
+    ///
+    /// Unlike the loaders, it doesn't check that the givens agree,
+    /// only that they make a grid.
+    pub fn read(self, src: &str, alphabet: &Alphabet) -> Result<Vec<Option<u32>>, LoadingError> {
+        let cells = match self {
+            Layout::Strict => Cells::read(located(src), true, alphabet)?,
+            Layout::Line => collection_cells(src, alphabet)
+                .next()
+                .unwrap_or(Err(LoadingError::InvalidSize { received: 0 }))?,
+            Layout::Sdk => sdk_cells(src, alphabet)?,
+            Layout::Ss => ss_cells(src, alphabet)?,
+            Layout::Numbers => number_cells(src)?,
+        };
+        let received = cells.values.len();
+        let size =
+            AnySudoku::size_of_len(received).ok_or(LoadingError::InvalidSize { received })?;
+        Ok(cells.values(size)?.to_vec())
+    }
+}
+
+// Nothing is written when some values have no symbol
+fn check_symbols(n: usize, alphabet: &Alphabet) -> Result<(), std::io::Error> {
+    match alphabet.covers(n) {
+        true => Ok(()),
+        false => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!(
+                "{} values but only {} symbols, write numbers instead",
+                n * n,
+                alphabet.len()
+            ),
+        )),
+    }
+}
+
+fn symbol(value: Option<u32>, alphabet: &Alphabet) -> char {
+    match value {
+        Some(value) => alphabet.symbol(value).unwrap_or('?'),
+        None => '.',
+    }
+}
+
+fn write_line(
+    mut writer: impl Write,
+    n: usize,
+    values: &[Option<u32>],
+    alphabet: &Alphabet,
+) -> Result<(), std::io::Error> {
+    check_symbols(n, alphabet)?;
+    for value in values {
+        write!(writer, "{}", symbol(*value, alphabet))?;
+    }
+    writeln!(writer)
+}
+
+// A row per line, with the squares separated in the `.ss` layout
+fn write_rows(
+    mut writer: impl Write,
+    n: usize,
+    values: &[Option<u32>],
+    squares: bool,
+    alphabet: &Alphabet,
+) -> Result<(), std::io::Error> {
+    check_symbols(n, alphabet)?;
+    let nn = n * n;
+    for (row, values) in values.chunks(nn).enumerate() {
+        if squares && row > 0 && row % n == 0 {
+            writeln!(writer, "{}", "-".repeat(nn + n - 1))?;
+        }
+        for (col, value) in values.iter().enumerate() {
+            if squares && col > 0 && col % n == 0 {
+                write!(writer, "|")?;
+            }
+            write!(writer, "{}", symbol(*value, alphabet))?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+// A row per line, numbers right-aligned and `0` for blanks
+fn write_numbers(
+    mut writer: impl Write,
+    n: usize,
+    values: &[Option<u32>],
+) -> Result<(), std::io::Error> {
+    let nn = n * n;
+    let width = nn.to_string().len();
+    for values in values.chunks(nn) {
+        let numbers: Vec<String> = values
+            .iter()
+            .map(|value| format!("{:width$}", value.map_or(0, |value| value + 1)))
+            .collect();
+        writeln!(writer, "{}", numbers.join(" "))?;
+    }
+    Ok(())
+}
+
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    /// Load a grid written with the symbols of the alphabet, `_` for blanks
+    ///
+    /// Only whitespace may separate the cells, any other character
+    /// is reported with its line and column.
+    pub fn from_str_strict(src: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        Cells::read(located(src), true, alphabet)?.load()
+    }
+
+    /// Load a puzzle written on one line, one character per cell
+    ///
+    /// `.`, `_` and `0`, unless it is one of the symbols of the grid,
+    /// are blanks. Whitespace is ignored.
+    pub fn from_line(line: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        line_cells(line, alphabet)?.load()
+    }
+
+    /// Load the puzzles of a collection, one per line
+    ///
+    /// Each line holds a puzzle in the format of [Sudoku::from_line],
+    /// followed by anything. Empty lines and `#` comments are skipped.
+    pub fn from_collection<'a>(
+        src: &'a str,
+        alphabet: &'a Alphabet,
+    ) -> impl Iterator<Item = Result<Self, LoadingError>> + 'a {
+        collection_cells(src, alphabet).map(|cells| cells?.load())
+    }
+
+    /// Load a puzzle in the SadMan `.sdk` layout, one row per line
+    pub fn from_sdk(src: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        sdk_cells(src, alphabet)?.load()
+    }
+
+    /// Load a puzzle in the Simple Sudoku `.ss` layout, one row per line,
+    /// squares separated by `|` and lines of `-`
+    pub fn from_ss(src: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        ss_cells(src, alphabet)?.load()
+    }
+
+    /// Load a grid written with numbers from `1` to `N*N`
+    ///
+    /// The numbers are separated by whitespace or commas,
+    /// `0`, `.` and `_` are blanks.
+    pub fn from_numbers(src: &str) -> Result<Self, LoadingError> {
+        number_cells(src)?.load()
+    }
+
+    // The values shown by the mask
+    fn shown(&self, mask: [[[[bool; N]; N]; N]; N]) -> Vec<Option<u32>> {
+        Pos::iter::<N>()
+            .map(|pos| mask[pos].then(|| self[pos].get_value()).flatten())
+            .collect()
+    }
+
+    /// Write the grid on one line, see [Sudoku::from_line]
+    ///
+    /// It fails with [std::io::ErrorKind::InvalidInput], writing nothing,
+    /// if the grid has more values than symbols, as the `.sdk` and `.ss`
+    /// layouts do. [Sudoku::write_numbers] suits any size.
+    pub fn write_line(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        write_line(writer, N, &self.shown(mask), alphabet)
+    }
+
+    /// Write the grid in the `.sdk` layout, see [Sudoku::from_sdk]
+    ///
+    /// It fails if the grid has more values than symbols, see [Sudoku::write_line].
+    pub fn write_sdk(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        write_rows(writer, N, &self.shown(mask), false, alphabet)
+    }
+
+    /// Write the grid in the `.ss` layout, see [Sudoku::from_ss]
+    ///
+    /// It fails if the grid has more values than symbols, see [Sudoku::write_line].
+    pub fn write_ss(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        write_rows(writer, N, &self.shown(mask), true, alphabet)
+    }
+
+    /// Write the grid with numbers, a row per line, see [Sudoku::from_numbers]
+    pub fn write_numbers(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+    ) -> Result<(), std::io::Error> {
+        write_numbers(writer, N, &self.shown(mask))
+    }
+}
+
+impl AnySudoku {
+    /// See [Sudoku::from_str_strict], the size is deduced from the number of cells
+    pub fn from_str_strict(src: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        Cells::read(located(src), true, alphabet)?.load_any()
+    }
+
+    /// See [Sudoku::from_line]
+    pub fn from_line(line: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        line_cells(line, alphabet)?.load_any()
+    }
+
+    /// See [Sudoku::from_collection], each puzzle may have its own size
+    pub fn from_collection<'a>(
+        src: &'a str,
+        alphabet: &'a Alphabet,
+    ) -> impl Iterator<Item = Result<Self, LoadingError>> + 'a {
+        collection_cells(src, alphabet).map(|cells| cells?.load_any())
+    }
+
+    /// See [Sudoku::from_sdk]
+    pub fn from_sdk(src: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        sdk_cells(src, alphabet)?.load_any()
+    }
+
+    /// See [Sudoku::from_ss]
+    pub fn from_ss(src: &str, alphabet: &Alphabet) -> Result<Self, LoadingError> {
+        ss_cells(src, alphabet)?.load_any()
+    }
+
+    /// See [Sudoku::from_numbers], the size is deduced from the number of cells
+    pub fn from_numbers(src: &str) -> Result<Self, LoadingError> {
+        number_cells(src)?.load_any()
+    }
+
+    fn shown(&self, mask: &[bool]) -> Vec<Option<u32>> {
+        self.values()
+            .into_iter()
+            .zip(mask)
+            .map(|(value, shown)| value.filter(|_| *shown))
+            .collect()
+    }
+
+    /// See [Sudoku::write_line]
+    pub fn write_line(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        write_line(writer, self.size(), &self.shown(mask), alphabet)
+    }
+
+    /// See [Sudoku::write_sdk]
+    pub fn write_sdk(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        write_rows(writer, self.size(), &self.shown(mask), false, alphabet)
+    }
+
+    /// See [Sudoku::write_ss]
+    pub fn write_ss(
+        &self,
+        writer: impl Write,
+        mask: &[bool],
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        write_rows(writer, self.size(), &self.shown(mask), true, alphabet)
+    }
+
+    /// See [Sudoku::write_numbers]
+    pub fn write_numbers(&self, writer: impl Write, mask: &[bool]) -> Result<(), std::io::Error> {
+        write_numbers(writer, self.size(), &self.shown(mask))
+    }
+}
+
+#[cfg(test)]
+const PUZZLE: &str =
+    "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+#[cfg(test)]
+fn givens() -> [[[[bool; 3]; 3]; 3]; 3] {
+    let mut mask = crate::mask_empty();
+    for (pos, c) in Pos::iter::<3>().zip(PUZZLE.chars()) {
+        mask[pos] = c != '.';
+    }
+    mask
+}
+
+#[test]
+fn line_and_collection() {
+    let alphabet = Alphabet::standard();
+    let grid = Sudoku::<3>::from_line(PUZZLE, &alphabet).unwrap();
+    let zeros = Sudoku::<3>::from_line(&PUZZLE.replace('.', "0"), &alphabet).unwrap();
+    let blanks = Sudoku::<3>::from_line(&PUZZLE.replace('.', "_"), &alphabet).unwrap();
+    assert_eq!(grid, zeros);
+    assert_eq!(grid, blanks);
+
+    let mut written = Vec::new();
+    grid.write_line(&mut written, givens(), &alphabet).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), format!("{PUZZLE}\n"));
+
+    let collection = format!("# hardest\n\n{PUZZLE} 11.9\n{PUZZLE}# again\n.....\n");
+    let puzzles: Vec<_> = Sudoku::<3>::from_collection(&collection, &alphabet).collect();
+    assert_eq!(puzzles.len(), 3);
+    assert_eq!(puzzles[0].as_ref().unwrap(), &grid);
+    assert_eq!(puzzles[1].as_ref().unwrap(), &grid);
+    assert_eq!(puzzles[2], Err(LoadingError::InvalidSize { received: 5 }));
+
+    let any = AnySudoku::from_line(PUZZLE, &alphabet).unwrap();
+    assert_eq!(any.size(), 3);
+}
+
+#[test]
+fn positional_errors() {
+    let alphabet = Alphabet::standard();
+    let text = "1 2 3 4\n_ _ _ _\n_ x _ _\n_ _ _ _\n";
+    let error = LoadingError::InvalidCharacter {
+        char: 'x',
+        line: 3,
+        column: 3,
+    };
+    assert_eq!(
+        Sudoku::<2>::from_str_strict(text, &alphabet),
+        Err(error.clone())
+    );
+    assert_eq!(AnySudoku::from_str_strict(text, &alphabet), Err(error));
+    assert_eq!(
+        Sudoku::<2>::from_str_strict("1 2 3 4\n_ _ _ _\n_ 5 _ _\n_ _ _ _\n", &alphabet),
+        Err(LoadingError::InvalidCharacter {
+            char: '5',
+            line: 3,
+            column: 3
+        })
+    );
+    // `.` is only a blank outside of the strict format
+    assert!(Sudoku::<2>::from_str_strict("1234____.___....", &alphabet).is_err());
+    assert_eq!(
+        Sudoku::<2>::from_str_strict("1 2 3", &alphabet),
+        Err(LoadingError::InvalidSize { received: 3 })
+    );
+    assert_eq!(
+        Sudoku::<2>::from_str_strict("1 2 3 4\n_ _ _ _\n_ _ _ 4\n_ _ _ _\n", &alphabet),
+        Err(LoadingError::Conflicting {
+            value: 3,
+            first: (0, 3),
+            second: (2, 3),
+        })
+    );
+    assert_eq!(
+        // the last column leaves 1 and 3 for the last square, which has a 3
+        Sudoku::<2>::from_str_strict("_ _ _ 2\n_ _ _ 4\n_ _ 3 _\n_ _ _ _\n", &alphabet),
+        Err(LoadingError::Contradiction {
+            value: 2,
+            row: 2,
+            col: 2,
+        })
+    );
+    assert_eq!(
+        LoadingError::Conflicting {
+            value: 3,
+            first: (0, 3),
+            second: (2, 3),
+        }
+        .to_string(),
+        "value 4 at row 3, column 4 clashes with the one at row 1, column 4"
+    );
+
+    let collection = format!("{PUZZLE}\n# comment\n  4....x\n");
+    let errors: Vec<_> = Sudoku::<3>::from_collection(&collection, &alphabet).collect();
+    assert_eq!(
+        errors[1],
+        Err(LoadingError::InvalidCharacter {
+            char: 'x',
+            line: 3,
+            column: 8
+        })
+    );
+}
+
+#[test]
+fn sdk_and_ss() {
+    let alphabet = Alphabet::standard();
+    let grid = Sudoku::<3>::from_line(PUZZLE, &alphabet).unwrap();
+
+    let mut sdk = Vec::new();
+    grid.write_sdk(&mut sdk, givens(), &alphabet).unwrap();
+    let sdk = String::from_utf8(sdk).unwrap();
+    assert_eq!(sdk.lines().next(), Some("4.....8.5"));
+    let header = format!("#AArto Inkala\n#DHard\n[Puzzle]\n{sdk}[State]\n123456789\n");
+    assert_eq!(Sudoku::<3>::from_sdk(&header, &alphabet).unwrap(), grid);
+
+    let mut ss = Vec::new();
+    grid.write_ss(&mut ss, givens(), &alphabet).unwrap();
+    let ss = String::from_utf8(ss).unwrap();
+    let lines: Vec<&str> = ss.lines().collect();
+    assert_eq!(lines.len(), 11);
+    assert_eq!(lines[0], "4..|...|8.5");
+    assert_eq!(lines[3], "-----------");
+    assert_eq!(Sudoku::<3>::from_ss(&ss, &alphabet).unwrap(), grid);
+    assert_eq!(AnySudoku::from_ss(&ss, &alphabet).unwrap().size(), 3);
+}
+
+#[test]
+fn custom_alphabets() {
+    // `0` is the first value in hexadecimal, not a blank
+    let hex = Alphabet::hex();
+    let grid = Sudoku::<2>::from_line("0123............", &hex).unwrap();
+    assert_eq!(
+        grid[Pos::from_row_col::<2>(0, 0).unwrap()].get_value(),
+        Some(0)
+    );
+    let mut written = Vec::new();
+    grid.write_line(&mut written, crate::mask_full(), &hex)
+        .unwrap();
+    assert!(String::from_utf8(written).unwrap().starts_with("0123"));
+
+    let wordoku = Alphabet::wordoku("word").unwrap();
+    let grid = Sudoku::<2>::from_line("WORD....0...____", &wordoku).unwrap();
+    assert_eq!(
+        grid[Pos::from_row_col::<2>(0, 3).unwrap()].get_value(),
+        Some(3)
+    );
+    assert_eq!(
+        Sudoku::<2>::from_line("WORDS...........", &wordoku),
+        Err(LoadingError::InvalidCharacter {
+            char: 'S',
+            line: 1,
+            column: 5
+        })
+    );
+
+    // four symbols can't write a 9×9 grid
+    let grid = Sudoku::<3>::from_line(PUZZLE, &Alphabet::standard()).unwrap();
+    let mut written = Vec::new();
+    let error = grid.write_ss(&mut written, givens(), &wordoku).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(written.is_empty());
+}
+
+#[test]
+fn numbers() {
+    let src = "1, 0, 0, 0\n0, 0, 3, 0\n. . . .\n_ _ _ 4\n";
+    let grid = Sudoku::<2>::from_numbers(src).unwrap();
+    assert_eq!(
+        grid[Pos::from_row_col::<2>(1, 2).unwrap()].get_value(),
+        Some(2)
+    );
+    assert_eq!(AnySudoku::from_numbers(src).unwrap().size(), 2);
+
+    let mut mask = crate::mask_empty();
+    let tokens = src.split(|c: char| c.is_whitespace() || c == ',');
+    for (pos, token) in Pos::iter::<2>().zip(tokens.filter(|t| !t.is_empty())) {
+        mask[pos] = !matches!(token, "0" | "." | "_");
+    }
+    let mut written = Vec::new();
+    grid.write_numbers(&mut written, mask).unwrap();
+    let written = String::from_utf8(written).unwrap();
+    assert_eq!(written.lines().next(), Some("1 0 0 0"));
+    assert_eq!(Sudoku::<2>::from_numbers(&written).unwrap(), grid);
+
+    let mut big = vec!["0"; 256];
+    big[17] = "16";
+    let grid = Sudoku::<4>::from_numbers(&big.join(",")).unwrap();
+    let mut written = Vec::new();
+    grid.write_numbers(&mut written, crate::mask_full())
+        .unwrap();
+    assert!(String::from_utf8(written).unwrap().contains(" 0 16  0"));
+
+    assert_eq!(
+        Sudoku::<2>::from_numbers("1 2 3 4\n0 0 0 0\n0 17 0 0\n0 0 0 0"),
+        Err(LoadingError::InvalidNumber {
+            number: "17".to_string(),
+            line: 3,
+            column: 3
+        })
+    );
+    assert_eq!(
+        Sudoku::<2>::from_numbers("1 2 3 4\n0 0 0 0\n0 1x 0 0\n0 0 0 0"),
+        Err(LoadingError::InvalidCharacter {
+            char: 'x',
+            line: 3,
+            column: 4
+        })
+    );
+}
@@ -1,12 +1,15 @@
 use std::ops::Index;
 
-use crate::{Cell, Defer, Pos};
+use crate::{Cell, Defer, Pos, Size, Storage};
 
 /// The sudoku grid with perfomed moves
 ///
 /// `N` The size of a square
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct Sudoku<const N: usize> {
+pub struct Sudoku<const N: usize>
+where
+    Size<N>: Storage,
+{
     /// Four dimensional array of Cell
     ///
     /// Refer to [Pos] for dimension order
@@ -17,7 +20,10 @@ pub struct Sudoku<const N: usize> {
     buckets: [[usize; N]; N],
 }
 
-impl<const N: usize> Sudoku<N> {
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
     pub fn best(&self) -> usize {
         for v_2 in 1..N {
             if self.buckets[0][v_2] != 0 {
@@ -39,7 +45,7 @@ impl<const N: usize> Sudoku<N> {
     pub fn remove_one(&mut self, value: u32, pos: Pos, pushed: &mut usize, defer: &mut Defer<N>) {
         self.grid[pos].remove(value);
         let len = self[pos].len();
-        *self.bucket(len - 0) -= 1;
+        *self.bucket(len) -= 1;
         *self.bucket(len - 1) += 1;
         self.moves.push((value, pos));
         defer.push(pos);
@@ -49,18 +55,40 @@ impl<const N: usize> Sudoku<N> {
         for _ in 0..n {
             let (value, pos) = self.moves.pop().unwrap();
             let len = self[pos].len();
-            *self.bucket(len - 0) += 1;
+            *self.bucket(len) += 1;
             *self.bucket(len - 1) -= 1;
             debug_assert!(!self[pos].contains(value));
             self.grid[pos] |= Cell::from_value(value);
         }
     }
+    /// The number of moves pushed so far
+    pub(crate) fn move_count(&self) -> usize {
+        self.moves.len()
+    }
+    /// The moves pushed after the first `from` ones, oldest first
+    pub(crate) fn moves_since(&self, from: usize) -> &[(u32, Pos)] {
+        &self.moves[from..]
+    }
+    /// Push again moves popped earlier, without propagating them
+    pub(crate) fn push_moves(&mut self, moves: &[(u32, Pos)]) {
+        for &(value, pos) in moves {
+            debug_assert!(self[pos].contains(value));
+            self.grid[pos].remove(value);
+            let len = self[pos].len();
+            *self.bucket(len) -= 1;
+            *self.bucket(len - 1) += 1;
+            self.moves.push((value, pos));
+        }
+    }
     pub const fn is_accepting(&self) -> bool {
         self.moves.len() == N * N * N * N * (N * N - 1)
     }
 }
 
-impl<const N: usize> Index<Pos> for Sudoku<N> {
+impl<const N: usize> Index<Pos> for Sudoku<N>
+where
+    Size<N>: Storage,
+{
     type Output = Cell<N>;
 
     fn index(&self, index: Pos) -> &Self::Output {
@@ -68,7 +96,10 @@ impl<const N: usize> Index<Pos> for Sudoku<N> {
     }
 }
 
-impl<const N: usize> Default for Sudoku<N> {
+impl<const N: usize> Default for Sudoku<N>
+where
+    Size<N>: Storage,
+{
     fn default() -> Self {
         let mut best = [[0; N]; N];
         best[N - 1][N - 1] = N * N * N * N;
@@ -0,0 +1,175 @@
+use std::iter::FusedIterator;
+
+use crate::{Cell, Correlated, Pos, Size, Storage, Sudoku};
+
+/// A group of `N*N` cells that must hold each value exactly once
+///
+/// Houses are numbered in `0..N*N`, squares row by row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum House {
+    Row(usize),
+    Col(usize),
+    Box(usize),
+}
+
+impl House {
+    /// All the houses of the grid, rows first, then columns, then squares
+    pub fn all<const N: usize>() -> impl Iterator<Item = House> {
+        let nn = N * N;
+        (0..nn)
+            .map(House::Row)
+            .chain((0..nn).map(House::Col))
+            .chain((0..nn).map(House::Box))
+    }
+
+    /// The cells of the house, in reading order
+    ///
+    /// Panics if the house is outside of a grid of size `N`.
+    pub fn cells<const N: usize>(self) -> HouseCells<N> {
+        let (House::Row(i) | House::Col(i) | House::Box(i)) = self;
+        assert!(i < N * N, "house {self:?} outside of a grid of size {N}");
+        HouseCells {
+            house: self,
+            index: 0,
+        }
+    }
+
+    /// Is the cell part of this house
+    pub fn contains<const N: usize>(self, pos: Pos) -> bool {
+        match self {
+            House::Row(i) => pos.row::<N>() == i,
+            House::Col(i) => pos.col::<N>() == i,
+            House::Box(i) => pos.box_index::<N>() == i,
+        }
+    }
+}
+
+impl std::fmt::Display for House {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            House::Row(i) => write!(f, "row {}", i + 1),
+            House::Col(i) => write!(f, "column {}", i + 1),
+            House::Box(i) => write!(f, "box {}", i + 1),
+        }
+    }
+}
+
+/// Iterator over the cells of a house, see [House::cells]
+#[derive(Debug, Clone)]
+pub struct HouseCells<const N: usize> {
+    house: House,
+    index: usize,
+}
+
+impl<const N: usize> Iterator for HouseCells<N> {
+    type Item = Pos;
+
+    fn next(&mut self) -> Option<Pos> {
+        let index = self.index;
+        if index >= N * N {
+            return None;
+        }
+        self.index += 1;
+        match self.house {
+            House::Row(row) => Pos::from_row_col::<N>(row, index),
+            House::Col(col) => Pos::from_row_col::<N>(index, col),
+            House::Box(i) => Pos::from_box::<N>(i, index),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (N * N).saturating_sub(self.index);
+        (len, Some(len))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for HouseCells<N> {}
+impl<const N: usize> FusedIterator for HouseCells<N> {}
+
+impl Pos {
+    /// The row, column and square of the cell
+    pub const fn houses<const N: usize>(self) -> [House; 3] {
+        [
+            House::Row(self.row::<N>()),
+            House::Col(self.col::<N>()),
+            House::Box(self.box_index::<N>()),
+        ]
+    }
+}
+
+/// The cells sharing a row, column or square with `pos`, without itself
+pub fn peers<const N: usize>(pos: Pos) -> Correlated<N> {
+    debug_assert!(pos.is_valid::<N>());
+    Correlated::new(pos)
+}
+
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    /// The cells of the house where `value` is still possible
+    pub fn positions_of(&self, house: House, value: u32) -> impl Iterator<Item = Pos> + '_ {
+        house
+            .cells::<N>()
+            .filter(move |pos| self[*pos].contains(value))
+    }
+
+    /// The values still possible somewhere in the house
+    pub fn house_candidates(&self, house: House) -> Cell<N> {
+        house
+            .cells::<N>()
+            .fold(Cell::EMPTY, |acc, pos| acc | self[pos])
+    }
+
+    /// The values already determined in the house
+    pub fn house_values(&self, house: House) -> Cell<N> {
+        house
+            .cells::<N>()
+            .filter_map(|pos| self[pos].get_value())
+            .fold(Cell::EMPTY, |acc, value| acc | Cell::from_value(value))
+    }
+}
+
+#[test]
+fn houses_and_peers() {
+    for pos in Pos::iter::<3>() {
+        let houses = pos.houses::<3>();
+        let mut expected: Vec<Pos> = houses
+            .iter()
+            .flat_map(|house| house.cells::<3>())
+            .filter(|other| *other != pos)
+            .collect();
+        expected.sort();
+        expected.dedup();
+        let mut peers: Vec<Pos> = peers::<3>(pos).collect();
+        peers.sort();
+        assert_eq!(peers, expected);
+        for house in House::all::<3>() {
+            assert_eq!(house.contains::<3>(pos), houses.contains(&house));
+        }
+    }
+    assert_eq!(House::all::<3>().count(), 27);
+    assert!(House::all::<3>().all(|house| house.cells::<3>().count() == 9));
+}
+
+#[test]
+fn candidates_in_house() {
+    use crate::Defer;
+
+    let mut grid = Sudoku::<2>::default();
+    let mut defer = Defer::new();
+    let pos = Pos::from_row_col::<2>(0, 0).unwrap();
+    grid.remove_all(!Cell::from_value(0), pos, &mut defer)
+        .unwrap();
+    assert_eq!(
+        grid.positions_of(House::Row(0), 0).collect::<Vec<_>>(),
+        [pos]
+    );
+    assert_eq!(grid.positions_of(House::Row(0), 1).count(), 3);
+    assert_eq!(
+        grid.positions_of(House::Box(0), 0).collect::<Vec<_>>(),
+        [pos]
+    );
+    assert_eq!(grid.house_candidates(House::Box(0)), Cell::FULL);
+    assert_eq!(grid.house_values(House::Box(0)), Cell::from_value(0));
+}
@@ -0,0 +1,199 @@
+use std::iter::FusedIterator;
+
+use crate::Pos;
+
+/// Iterator over all positions of the grid, row by row
+///
+/// See [Pos::iter]
+#[derive(Debug, Clone)]
+pub struct PosIter<const N: usize> {
+    next: Pos,
+    remaining: usize,
+}
+
+impl<const N: usize> PosIter<N> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            next: Pos {
+                x_1: 0,
+                x_2: 0,
+                y_1: 0,
+                y_2: 0,
+            },
+            remaining: N * N * N * N,
+        }
+    }
+}
+
+impl<const N: usize> Iterator for PosIter<N> {
+    type Item = Pos;
+
+    #[inline]
+    fn next(&mut self) -> Option<Pos> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        let pos = self.next;
+        let n = N as u8;
+        // increment the innermost dimension first, and carry
+        self.next.x_2 += 1;
+        if self.next.x_2 == n {
+            self.next.x_2 = 0;
+            self.next.x_1 += 1;
+            if self.next.x_1 == n {
+                self.next.x_1 = 0;
+                self.next.y_2 += 1;
+                if self.next.y_2 == n {
+                    self.next.y_2 = 0;
+                    self.next.y_1 += 1;
+                }
+            }
+        }
+        Some(pos)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for PosIter<N> {}
+impl<const N: usize> FusedIterator for PosIter<N> {}
+
+/// This allow to easily iterate over the correlated cells of one cell
+/// We call correlated cells the one in the same line, column or square
+///
+/// The row and the column are walked without the square of the cell,
+/// then the square is walked fully, except for the cell itself.
+/// See [peers](crate::peers)
+#[derive(Debug, Clone)]
+pub struct Correlated<const N: usize> {
+    pos: Pos,
+    /// The next cell to yield
+    next: Pos,
+    /// `0` row, `1` column, `2` square, `3` done
+    phase: u8,
+    remaining: usize,
+}
+
+impl<const N: usize> Correlated<N> {
+    pub(crate) const fn new(pos: Pos) -> Self {
+        let mut next = pos;
+        next.x_1 = (pos.x_1 == 0) as u8;
+        next.x_2 = 0;
+        Self {
+            pos,
+            next,
+            phase: if N > 1 { 0 } else { 3 },
+            // both lines without the square, and the square without the cell
+            remaining: 2 * N.saturating_sub(1) * N + (N * N).saturating_sub(1),
+        }
+    }
+}
+
+impl<const N: usize> Iterator for Correlated<N> {
+    type Item = Pos;
+
+    #[inline]
+    fn next(&mut self) -> Option<Pos> {
+        let n = N as u8;
+        let pos = self.pos;
+        let next = self.next;
+        match self.phase {
+            // row (without square)
+            0 => {
+                self.next.x_2 += 1;
+                if self.next.x_2 == n {
+                    self.next.x_2 = 0;
+                    self.next.x_1 += 1;
+                    if self.next.x_1 == pos.x_1 {
+                        self.next.x_1 += 1;
+                    }
+                    if self.next.x_1 >= n {
+                        self.next = pos;
+                        self.next.y_1 = (pos.y_1 == 0) as u8;
+                        self.next.y_2 = 0;
+                        self.phase = 1;
+                    }
+                }
+            }
+            // column (without square)
+            1 => {
+                self.next.y_2 += 1;
+                if self.next.y_2 == n {
+                    self.next.y_2 = 0;
+                    self.next.y_1 += 1;
+                    if self.next.y_1 == pos.y_1 {
+                        self.next.y_1 += 1;
+                    }
+                    if self.next.y_1 >= n {
+                        self.next = pos;
+                        self.next.y_2 = 0;
+                        self.next.x_2 = (pos.y_2 == 0 && pos.x_2 == 0) as u8;
+                        self.phase = 2;
+                    }
+                }
+            }
+            // square (full)
+            2 => {
+                self.next.x_2 += 1;
+                if self.next.x_2 == n {
+                    self.next.x_2 = 0;
+                    self.next.y_2 += 1;
+                }
+                if self.next.y_2 == pos.y_2 && self.next.x_2 == pos.x_2 {
+                    self.next.x_2 += 1;
+                    if self.next.x_2 == n {
+                        self.next.x_2 = 0;
+                        self.next.y_2 += 1;
+                    }
+                }
+                if self.next.y_2 == n {
+                    self.phase = 3;
+                }
+            }
+            _ => return None,
+        }
+        self.remaining -= 1;
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for Correlated<N> {}
+impl<const N: usize> FusedIterator for Correlated<N> {}
+
+#[test]
+fn pos_iter_row_by_row() {
+    let mut expected = Vec::new();
+    for y_1 in 0..3 {
+        for y_2 in 0..3 {
+            for x_1 in 0..3 {
+                for x_2 in 0..3 {
+                    expected.push(Pos { y_1, y_2, x_1, x_2 });
+                }
+            }
+        }
+    }
+    assert_eq!(Pos::iter::<3>().collect::<Vec<_>>(), expected);
+    assert_eq!(Pos::iter::<0>().count(), 0);
+    assert_eq!(Pos::iter::<1>().count(), 1);
+}
+
+#[test]
+fn correlated_cells() {
+    for pos in Pos::iter::<3>() {
+        let cells: Vec<Pos> = Correlated::<3>::new(pos).collect();
+        assert_eq!(cells.len(), 20);
+        let mut sorted = cells.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(sorted.len(), 20);
+        assert!(!cells.contains(&pos));
+        assert!(cells.iter().all(|other| pos.is_correlated(*other)));
+    }
+}
@@ -1,84 +1,157 @@
-#![feature(gen_blocks)]
-
+mod alphabet;
+mod any;
+mod batch;
+mod bits;
+mod board;
+mod canonical;
 mod cell;
-mod charset;
+mod check;
 mod defer;
+mod format;
 mod grid;
+mod house;
+mod iter;
+mod logic;
+mod nogood;
+mod pdf;
+mod pos;
+mod sample;
+#[cfg(feature = "serde")]
+mod serialize;
+mod solutions;
+mod style;
+mod svg;
+mod transform;
 
+pub use alphabet::Alphabet;
+pub use any::{AnySudoku, ChooseAny};
+pub use batch::{BatchOptions, BatchSummary, Solved, solve_batch};
+pub use bits::{Bits, Size, Storage};
+pub use board::{Board, EditError};
 pub use cell::Cell;
-pub use charset::{char_to_value, value_to_char, value_to_char_width};
+pub use check::Check;
 pub use defer::Defer;
+pub use format::Layout;
 pub use grid::Sudoku;
+pub use house::{House, HouseCells, peers};
+pub use iter::{Correlated, PosIter};
+pub use logic::{Difficulty, Grade, Logic, Step, StepDisplay, Technique};
+use nogood::Conflict;
+pub use pdf::Booklet;
+pub use pos::Pos;
 use rand::prelude::*;
 use rand::{SeedableRng, rngs::SmallRng};
-use std::{
-    io::Write,
-    ops::{Index, IndexMut},
-};
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
-pub struct Pos {
-    /// Selects the row chunk
-    x_1: u8,
-    /// Selects the row line
-    x_2: u8,
-    /// Selects the column chunk
-    y_1: u8,
-    /// Selects the column line
-    y_2: u8,
-}
+pub use sample::Sampler;
+#[cfg(feature = "serde")]
+pub use serialize::serde_mask;
+pub use solutions::Solutions;
+use std::io::Write;
+use style::{Borders, RESET};
+pub use style::{Shade, Style};
+pub use svg::{Cage, Dot, Markings, SvgOptions};
+pub use transform::Transform;
 
-impl<const N: usize> Sudoku<N> {
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
     pub const TTL: usize = 1 << (N + 5);
     pub fn encode_grid(&self, dst: &mut [u8], mask: [[[[bool; N]; N]; N]; N]) {
         assert!(dst.len() >= N * N * N * N);
-        let mut i = 0;
-        for pos in Pos::iter::<N>() {
+        for (i, pos) in Pos::iter::<N>().enumerate() {
             dst[i] = mask[pos]
                 .then_some(self[pos])
                 .and_then(|c| c.get_value())
                 .map(|v| v as u8)
                 .unwrap_or(255);
-            i += 1;
         }
     }
+    /// Load a grid, one value per cell in the order of [Pos::iter]
+    ///
+    /// Two givens clashing in a house are reported before any value
+    /// ruled out by the propagation of the others.
+    pub fn from_values(values: &[Option<u32>]) -> Result<Self, LoadingError> {
+        let received = values.len();
+        if received != N * N * N * N {
+            return Err(LoadingError::InvalidSize { received });
+        }
+        for (pos, value) in Pos::iter::<N>().zip(values) {
+            let Some(value) = *value else {
+                continue;
+            };
+            let (row, col) = pos.row_col::<N>();
+            if value >= Cell::<N>::R {
+                return Err(LoadingError::InvalidValue { value, row, col });
+            }
+            let clash = peers::<N>(pos)
+                .filter(|other| other.index::<N>() < pos.index::<N>())
+                .find(|other| values[other.index::<N>()] == Some(value));
+            if let Some(other) = clash {
+                return Err(LoadingError::Conflicting {
+                    value,
+                    first: other.row_col::<N>(),
+                    second: (row, col),
+                });
+            }
+        }
+        let mut grid = Self::default();
+        let mut defer = Defer::new();
+        for (pos, value) in Pos::iter::<N>().zip(values) {
+            let cell = value.map_or(Cell::FULL, Cell::from_value);
+            if grid.remove_all(!cell, pos, &mut defer).is_none() {
+                let (row, col) = pos.row_col::<N>();
+                return Err(LoadingError::Contradiction {
+                    value: value.unwrap_or_default(),
+                    row,
+                    col,
+                });
+            }
+        }
+        Ok(grid)
+    }
     pub fn decode_grid(src: &[u8]) -> Option<Self> {
         assert!(src.len() >= N * N * N * N);
         let mut defer = Defer::new();
         let mut grid = Self::default();
-        let mut i = 0;
-        for pos in Pos::iter::<N>() {
+        for (i, pos) in Pos::iter::<N>().enumerate() {
             let cell = match src[i] {
                 255 => Cell::FULL,
                 value => Cell::from_value(value as u32),
             };
             grid.remove_all(!cell, pos, &mut defer)?;
-            i += 1;
         }
         Some(grid)
     }
-    fn remove(&mut self, value: u32, pos: Pos, defer: &mut Defer<N>) -> Option<usize> {
+    // Remove the value from the cell, and cascade
+    //
+    // On failure, the moves pushed so far are left on the grid,
+    // counted in `pushed`, for the caller to explain the conflict.
+    fn remove(
+        &mut self,
+        value: u32,
+        pos: Pos,
+        pushed: &mut usize,
+        defer: &mut Defer<N>,
+    ) -> Result<(), Conflict<N>> {
         debug_assert!(self[pos].contains(value));
         if self[pos] == Cell::from_value(value) {
-            return None;
+            return Err(Conflict::Last { pos, value });
         }
         defer.clear();
 
-        let mut pushed = 0;
-        self.remove_one(value, pos, &mut pushed, defer);
+        self.remove_one(value, pos, pushed, defer);
 
         while let Some(pos) = defer.pop() {
             // if the current cell has a unique possiblity
             // all correlated cells can't have it
             if let Some(value) = self[pos].get_value() {
-                for pos in correlated::<N>(pos) {
-                    if self[pos].contains(value) {
-                        if self[pos] == Cell::from_value(value) {
-                            self.pop_n_moves(pushed);
-                            return None;
+                for other in Correlated::<N>::new(pos) {
+                    if self[other].contains(value) {
+                        if self[other] == Cell::from_value(value) {
+                            return Err(Conflict::Twice { pos, other, value });
                         }
-                        debug_assert!(self[pos].contains(value));
-                        self.remove_one(value, pos, &mut pushed, defer);
+                        debug_assert!(self[other].contains(value));
+                        self.remove_one(value, other, pushed, defer);
                     }
                 }
             }
@@ -86,7 +159,7 @@ impl<const N: usize> Sudoku<N> {
             // Now that we removed the `value` possibility of the cell `[y, x]`
             // Maybe a correlated cell now is the only one with it in its correlated neigbourhood
             // If it is the case, it become its only possibility, and we cascade the effect
-            for pos in correlated::<N>(pos) {
+            for pos in Correlated::<N>::new(pos) {
                 // A determine cell will always result in enforcing its value
                 // It is already unique, so we don't have to do anything
                 if self[pos].len() == 1 {
@@ -95,81 +168,107 @@ impl<const N: usize> Sudoku<N> {
                 let unic =
                     self.unic_on_row(pos) | self.unic_on_column(pos) | self.unic_on_square(pos);
 
-                if unic.len() == 0 {
+                if unic.is_empty() {
                     continue;
                 }
 
                 let Some(value) = unic.get_value() else {
                     // more than one value is enforce in the cell, leading to incoherence
-                    self.pop_n_moves(pushed);
-                    return None;
+                    return Err(Conflict::Hidden { pos, values: unic });
                 };
 
                 if !self[pos].contains(value) {
-                    self.pop_n_moves(pushed);
-                    return None;
+                    return Err(Conflict::Hidden { pos, values: unic });
                 }
                 for iv in self[pos] - value {
-                    if self[pos] == Cell::from_value(value) {
-                        self.pop_n_moves(pushed);
-                        return None;
-                    }
                     debug_assert!(self[pos].contains(iv));
-                    self.remove_one(iv, pos, &mut pushed, defer);
+                    self.remove_one(iv, pos, pushed, defer);
                 }
             }
         }
-        Some(pushed)
+        Ok(())
+    }
+    pub fn brute_force<C, T>(&mut self, chooser: C, ttl: T) -> Solutions<'_, N, C, T::IntoIter>
+    where
+        C: Choose<N>,
+        T: IntoIterator<Item = usize>,
+    {
+        self.brute_force_learning(chooser, ttl, 0)
     }
-    pub fn brute_force(
+
+    /// Same as [Sudoku::brute_force], with conflict-driven learning
+    ///
+    /// When a choice fails, the values held or removed before it that the
+    /// conflict follows from are remembered as a nogood, and enforced for
+    /// the rest of the search. When every value of a cell fails, the search
+    /// goes straight back to the last choice they depend on.
+    /// At most `capacity` nogoods are kept, `0` disables learning.
+    ///
+    /// The `learning` benchmark compares both. Proving its hard 9×9 puzzles
+    /// unique takes 43% fewer steps, none saved on 5 of the 11, in 2.5 times
+    /// the time. Generating full grids from 32 seeds, the time to live runs
+    /// out on none instead of 3 at 36×36, in 1.15 times the time, and on 3
+    /// instead of 8 at 49×49, in 1.3 times the time.
+    pub fn brute_force_learning<C, T>(
         &mut self,
-        mut chooser: impl Choose<N>,
-        ttl: impl IntoIterator<Item = usize>,
-    ) -> impl Iterator<Item = Self> {
-        gen move {
-            let min = self.best();
-            if min == 1 {
-                yield self.clone();
-                return;
-            }
-            // let pos_iter = chooser.pos_iter();
-            let mut pos = self.min_bifurc(min);
-            let mut cell = self[pos];
-
-            let mut stack: Vec<(usize, Cell<N>, Pos)> = Vec::new();
-            let mut persist = Defer::<N>::new();
-
-            for i in ttl {
-                if let Some(value) = chooser.choose_pop_value_in_cell(&mut cell) {
-                    if let Some(moved) =
-                        self.remove_all(!Cell::from_value(value), pos, &mut persist)
-                    {
-                        match self.best() {
-                            1 => {
-                                println!("{i}");
-                                yield self.clone();
-                                self.pop_n_moves(moved);
-                            }
-                            min => {
-                                stack.push((moved, cell, pos));
-                                pos = self.min_bifurc(min);
-                                cell = self[pos];
-                            }
-                        }
-                    }
-                } else {
-                    let Some((unpush, prev_cell, prev_pos)) = stack.pop() else {
-                        return;
-                    };
-                    self.pop_n_moves(unpush);
-                    cell = prev_cell;
-                    pos = prev_pos;
-                }
-            }
-            for (unpush, _, _) in stack {
-                self.pop_n_moves(unpush);
-            }
+        chooser: C,
+        ttl: T,
+        capacity: usize,
+    ) -> Solutions<'_, N, C, T::IntoIter>
+    where
+        C: Choose<N>,
+        T: IntoIterator<Item = usize>,
+    {
+        Solutions::new(self, chooser, ttl.into_iter(), capacity)
+    }
+
+    /// Generate a random full grid
+    ///
+    /// It fails if the search runs out of its time to live, see [Sudoku::TTL].
+    pub fn generate_full<R: RngChild + Choose<N>>(rng: &mut R) -> Option<Self> {
+        Self::generate_full_learning(rng, 0)
+    }
+
+    /// Same as [Sudoku::generate_full], see [Sudoku::brute_force_learning]
+    pub fn generate_full_learning<R: RngChild + Choose<N>>(
+        rng: &mut R,
+        capacity: usize,
+    ) -> Option<Self> {
+        Self::default()
+            .brute_force_learning(rng.rng_child(), 0..Self::TTL, capacity)
+            .next()
+    }
+
+    /// The full grid whose rows are the first one, `1` to `N²`, shifted
+    /// by `N` for each row of a band and by one for each band
+    pub fn pattern() -> Self {
+        Self::filled(Self::pattern_value)
+    }
+
+    fn pattern_value(pos: Pos) -> u32 {
+        let (row, col) = pos.row_col::<N>();
+        ((N * (row % N) + row / N + col) % (N * N)) as u32
+    }
+
+    // The full grid with the given value at each position, which must be valid
+    pub(crate) fn filled(value: impl Fn(Pos) -> u32) -> Self {
+        let mut defer = Defer::new();
+        let mut grid = Self::default();
+        for pos in Pos::iter::<N>() {
+            grid.remove_all(!Cell::from_value(value(pos)), pos, &mut defer)
+                .expect("the values form a valid grid");
         }
+        grid
+    }
+
+    /// A random full grid, scrambling [Sudoku::pattern] with [Transform::random]
+    ///
+    /// Unlike [Sudoku::generate_full] it never fails and takes no time even
+    /// for the largest grids, but it only reaches the grids isomorphic to the pattern.
+    pub fn generate_shuffled(rng: impl Rng) -> Self {
+        let transform = Transform::<N>::random(rng);
+        let inverse = transform.inverse();
+        Self::filled(|pos| transform.map_value(Self::pattern_value(inverse.map_pos(pos))))
     }
 
     // Because of the way moves are pushed, it enforces that the grid
@@ -196,18 +295,31 @@ impl<const N: usize> Sudoku<N> {
     #[must_use]
     pub fn remove_all(&mut self, values: Cell<N>, pos: Pos, defer: &mut Defer<N>) -> Option<usize> {
         let mut count = 0;
+        match self.remove_all_traced(values, pos, &mut count, defer) {
+            Ok(()) => Some(count),
+            Err(_) => {
+                self.pop_n_moves(count);
+                None
+            }
+        }
+    }
+
+    /// Same as [Sudoku::remove_all], but on failure the moves are left
+    /// on the grid, counted in `pushed`, with the reason of the conflict
+    pub(crate) fn remove_all_traced(
+        &mut self,
+        values: Cell<N>,
+        pos: Pos,
+        pushed: &mut usize,
+        defer: &mut Defer<N>,
+    ) -> Result<(), Conflict<N>> {
         for iv in self[pos] & values {
             // always check again, because the value may have been removed meanwhile
             if self[pos].contains(iv) {
-                if let Some(n) = self.remove(iv, pos, defer) {
-                    count += n;
-                } else {
-                    self.pop_n_moves(count);
-                    return None;
-                }
+                self.remove(iv, pos, pushed, defer)?;
             }
         }
-        Some(count)
+        Ok(())
     }
 
     // For a given cell, returns all possibilities of the cell
@@ -284,64 +396,95 @@ impl<const N: usize> Sudoku<N> {
 
     pub fn print(
         &self,
-        mut writer: impl Write,
+        writer: impl Write,
         mask: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
     ) -> Result<(), std::io::Error> {
-        fn print_line_sep(
-            mut writer: impl Write,
-            n: usize,
-            left: char,
-            right: char,
-            line: char,
-            cross_thin: char,
-            cross_bold: char,
-        ) -> Result<(), std::io::Error> {
-            let nn = n * n;
-            write!(writer, "{left}{line}{line}{line}")?;
-            for x in 1..nn {
-                if x % n == 0 {
-                    write!(writer, "{cross_bold}{line}{line}{line}")?;
-                } else {
-                    write!(writer, "{cross_thin}{line}{line}{line}")?;
-                }
-            }
-            writeln!(writer, "{right}")?;
-            Ok(())
-        }
-        print_line_sep(&mut writer, N, '┏', '┓', '━', '┯', '┳')?;
+        self.print_styled(writer, alphabet, Style::plain(), |pos, cell| {
+            Some((cell.get_value().filter(|_| mask[pos])?, Shade::Given))
+        })
+    }
+
+    /// Print the values of the mask, telling the givens from the values found by the solver
+    pub fn print_solution(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+        givens: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
+        style: Style,
+    ) -> Result<(), std::io::Error> {
+        self.print_styled(writer, alphabet, style, |pos, cell| {
+            let shade = if givens[pos] {
+                Shade::Given
+            } else {
+                Shade::Solved
+            };
+            Some((cell.get_value().filter(|_| mask[pos])?, shade))
+        })
+    }
+
+    /// Print the values given by `shown`, with their shade
+    ///
+    /// `shown` receives each cell of the grid, and returns the value to show
+    /// in it, if any. It need not be a value of the cell, which lets a player's
+    /// conflicting entries be drawn.
+    pub fn print_styled(
+        &self,
+        mut writer: impl Write,
+        alphabet: &Alphabet,
+        style: Style,
+        shown: impl Fn(Pos, Cell<N>) -> Option<(u32, Shade)>,
+    ) -> Result<(), std::io::Error> {
+        let borders = Borders::of(style);
+        // Beyond the symbols, values are written as numbers starting at 1
+        let digits = match alphabet.covers(N) {
+            true => None,
+            false => Some(Cell::<N>::R.to_string().len()),
+        };
+        let width = digits.map_or(3, |digits| digits + 2);
+        let bold = borders.bold.to_string().repeat(width);
+        let thin = borders.thin.to_string().repeat(width);
+        let [top, bold_sep, thin_sep, bottom] = borders.separators;
+        print_line_sep(&mut writer, N, top, &bold)?;
         for y_1 in 0..N as u8 {
             for y_2 in 0..N as u8 {
                 if y_1 > 0 || y_2 > 0 {
                     if y_2 == 0 {
-                        print_line_sep(&mut writer, N, '┣', '┫', '━', '┿', '╋')?;
+                        print_line_sep(&mut writer, N, bold_sep, &bold)?;
                     } else {
-                        print_line_sep(&mut writer, N, '┠', '┨', '─', '┼', '╂')?;
+                        print_line_sep(&mut writer, N, thin_sep, &thin)?;
                     }
                 }
                 for x_1 in 0..N as u8 {
                     for x_2 in 0..N as u8 {
                         if x_2 == 0 {
-                            write!(writer, "┃")?;
+                            write!(writer, "{}", borders.bold_side)?;
                         } else {
-                            write!(writer, "│")?;
+                            write!(writer, "{}", borders.thin_side)?;
                         }
                         let pos = Pos { y_1, y_2, x_1, x_2 };
 
-                        match Some(pos)
-                            .filter(|p| mask[*p])
-                            .and_then(|p| self[p].get_value())
-                        {
-                            None => {
-                                write!(writer, "   ")?;
+                        let Some((value, shade)) = shown(pos, self[pos]) else {
+                            write!(writer, "{:width$}", "")?;
+                            continue;
+                        };
+                        let (start, end) = match style.colors {
+                            true => (shade.ansi(), RESET),
+                            false => ("", ""),
+                        };
+                        match digits {
+                            Some(digits) => {
+                                write!(writer, " {start}{:>digits$}{end} ", value + 1)?;
                             }
-                            Some(value) => {
-                                let c = value_to_char(value).unwrap();
-                                match value_to_char_width(value).unwrap() {
+                            None => {
+                                let c = alphabet.symbol(value).unwrap();
+                                match alphabet.width(value).unwrap() {
                                     1 => {
-                                        write!(writer, " {c} ")?;
+                                        write!(writer, " {start}{c}{end} ")?;
                                     }
                                     2 => {
-                                        write!(writer, " {c}")?;
+                                        write!(writer, " {start}{c}{end}")?;
                                     }
                                     _ => unreachable!(),
                                 }
@@ -349,10 +492,95 @@ impl<const N: usize> Sudoku<N> {
                         };
                     }
                 }
-                writeln!(writer, "┃")?;
+                writeln!(writer, "{}", borders.bold_side)?;
             }
         }
-        print_line_sep(&mut writer, N, '┗', '┛', '━', '┷', '┻')?;
+        print_line_sep(&mut writer, N, bottom, &bold)?;
+        Ok(())
+    }
+
+    /// Print the candidates of every cell, as a mini-grid of `N` by `N`
+    ///
+    /// The value of a determined cell is shown alone, in the middle of its cell.
+    /// Only the borders of the style apply, the candidates are not coloured.
+    pub fn print_candidates(
+        &self,
+        writer: impl Write,
+        alphabet: &Alphabet,
+        style: Style,
+    ) -> Result<(), std::io::Error> {
+        self.print_marks(writer, alphabet, style, |_, cell| {
+            cell.get_value().ok_or(cell)
+        })
+    }
+
+    /// Print pencil marks like [Sudoku::print_candidates], chosen by `marks`
+    ///
+    /// `marks` receives each cell of the grid, and returns the value shown alone
+    /// in it, or else the candidates to show, which need not be the ones of the cell.
+    pub fn print_marks(
+        &self,
+        mut writer: impl Write,
+        alphabet: &Alphabet,
+        style: Style,
+        marks: impl Fn(Pos, Cell<N>) -> Result<u32, Cell<N>>,
+    ) -> Result<(), std::io::Error> {
+        // Beyond the symbols, values are written as numbers starting at 1
+        let digits = match alphabet.covers(N) {
+            true => None,
+            false => Some(Cell::<N>::R.to_string().len()),
+        };
+        let columns = digits.unwrap_or_else(|| {
+            (0..Cell::<N>::R)
+                .filter_map(|value| alphabet.width(value))
+                .max()
+                .unwrap_or(1) as usize
+        });
+        let width = N * (columns + 1) + 1;
+        let borders = Borders::of(style);
+        let bold = borders.bold.to_string().repeat(width);
+        let thin = borders.thin.to_string().repeat(width);
+        let [top, bold_sep, thin_sep, bottom] = borders.separators;
+        print_line_sep(&mut writer, N, top, &bold)?;
+        for row in 0..N * N {
+            if row > 0 {
+                if row % N == 0 {
+                    print_line_sep(&mut writer, N, bold_sep, &bold)?;
+                } else {
+                    print_line_sep(&mut writer, N, thin_sep, &thin)?;
+                }
+            }
+            for line in 0..N {
+                for col in 0..N * N {
+                    match col % N {
+                        0 => write!(writer, "{}", borders.bold_side)?,
+                        _ => write!(writer, "{}", borders.thin_side)?,
+                    }
+                    let pos = Pos::from_row_col::<N>(row, col).unwrap();
+                    let marked = marks(pos, self[pos]);
+                    for k in 0..N {
+                        let slot = (line * N + k) as u32;
+                        let shown = match marked {
+                            Ok(value) => (line == N / 2 && k == N / 2).then_some(value),
+                            Err(cell) => cell.contains(slot).then_some(slot),
+                        };
+                        match shown {
+                            None => write!(writer, " {:columns$}", "")?,
+                            Some(value) if digits.is_some() => {
+                                write!(writer, " {:>columns$}", value + 1)?
+                            }
+                            Some(value) => {
+                                let pad = columns - alphabet.width(value).unwrap() as usize;
+                                write!(writer, " {}{:pad$}", alphabet.symbol(value).unwrap(), "")?
+                            }
+                        }
+                    }
+                    write!(writer, " ")?;
+                }
+                writeln!(writer, "{}", borders.bold_side)?;
+            }
+        }
+        print_line_sep(&mut writer, N, bottom, &bold)?;
         Ok(())
     }
 
@@ -373,120 +601,92 @@ impl<const N: usize> Sudoku<N> {
     }
 }
 
-impl Pos {
-    pub fn iter<const N: usize>() -> impl Iterator<Item = Pos> {
-        gen {
-            for y_1 in 0..N as u8 {
-                for y_2 in 0..N as u8 {
-                    for x_1 in 0..N as u8 {
-                        for x_2 in 0..N as u8 {
-                            yield Pos { y_1, y_2, x_1, x_2 };
-                        }
-                    }
-                }
-            }
-        }
-    }
-}
-
-impl<const N: usize> Index<Pos> for [[[[Cell<N>; N]; N]; N]; N] {
-    type Output = Cell<N>;
-
-    #[inline]
-    fn index(&self, index: Pos) -> &Self::Output {
-        unsafe {
-            self.get_unchecked(index.y_1 as usize)
-                .get_unchecked(index.y_2 as usize)
-                .get_unchecked(index.x_1 as usize)
-                .get_unchecked(index.x_2 as usize)
-        }
-    }
-}
-impl<const N: usize> IndexMut<Pos> for [[[[Cell<N>; N]; N]; N]; N] {
-    #[inline]
-    fn index_mut(&mut self, index: Pos) -> &mut Self::Output {
-        unsafe {
-            self.get_unchecked_mut(index.y_1 as usize)
-                .get_unchecked_mut(index.y_2 as usize)
-                .get_unchecked_mut(index.x_1 as usize)
-                .get_unchecked_mut(index.x_2 as usize)
-        }
-        // &mut self[index.y_1 as usize][index.y_2 as usize][index.x_1 as usize][index.x_2 as usize]
-    }
-}
-impl<const N: usize> Index<Pos> for [[[[bool; N]; N]; N]; N] {
-    type Output = bool;
-
-    #[inline]
-    fn index(&self, index: Pos) -> &Self::Output {
-        unsafe {
-            self.get_unchecked(index.y_1 as usize)
-                .get_unchecked(index.y_2 as usize)
-                .get_unchecked(index.x_1 as usize)
-                .get_unchecked(index.x_2 as usize)
-        }
-    }
-}
-impl<const N: usize> IndexMut<Pos> for [[[[bool; N]; N]; N]; N] {
-    #[inline]
-    fn index_mut(&mut self, index: Pos) -> &mut Self::Output {
-        unsafe {
-            self.get_unchecked_mut(index.y_1 as usize)
-                .get_unchecked_mut(index.y_2 as usize)
-                .get_unchecked_mut(index.x_1 as usize)
-                .get_unchecked_mut(index.x_2 as usize)
-        }
-    }
-}
-
-// This allow to easily iterate over the correlated cells of one cell
-// We call correlated cells the one in the same line, column or square
-fn correlated<const N: usize>(pos: Pos) -> impl Iterator<Item = Pos> {
-    gen move {
-        let n = N as u8;
-        // row (without square)
-        for x_1 in 0..n {
-            if x_1 != pos.x_1 {
-                for x_2 in 0..n {
-                    yield Pos { x_1, x_2, ..pos };
-                }
-            }
-        }
-        // column (without square)
-        for y_1 in 0..n {
-            if y_1 != pos.y_1 {
-                for y_2 in 0..n {
-                    yield Pos { y_1, y_2, ..pos };
-                }
-            }
-        }
-        // square (full)
-        for y_2 in 0..n {
-            for x_2 in 0..n {
-                if y_2 != pos.y_2 || x_2 != pos.x_2 {
-                    yield Pos { y_2, x_2, ..pos };
-                }
-            }
-        }
-    }
-}
-
-#[derive(Debug)]
+/// Why a grid could not be loaded
+///
+/// Lines and columns of the text are counted from 1,
+/// rows and columns of the grid from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum LoadingError {
+    /// A character that is neither a symbol of the grid nor a blank
     InvalidCharacter {
         char: char,
+        line: usize,
+        column: usize,
     },
-    InvalidSize {
-        received: usize,
+    /// A number outside of `1..=N*N`, for grids written with numbers
+    InvalidNumber {
+        number: String,
+        line: usize,
+        column: usize,
     },
+    /// A value outside of `0..N*N`
+    InvalidValue { value: u32, row: usize, col: usize },
+    /// The number of cells doesn't make a grid of the expected size
+    InvalidSize { received: usize },
+    /// Two cells of the same row, column or square hold the same value
     Conflicting {
-        pos_x: usize,
-        pos_y: usize,
         value: u32,
+        first: (usize, usize),
+        second: (usize, usize),
     },
+    /// The value was ruled out by the other givens, without a direct clash
+    Contradiction { value: u32, row: usize, col: usize },
+}
+
+impl std::fmt::Display for LoadingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            LoadingError::InvalidCharacter { char, line, column } => {
+                write!(
+                    f,
+                    "invalid character {char:?} at line {line}, column {column}"
+                )
+            }
+            LoadingError::InvalidNumber {
+                ref number,
+                line,
+                column,
+            } => write!(f, "invalid number {number} at line {line}, column {column}"),
+            LoadingError::InvalidValue { value, row, col } => write!(
+                f,
+                "invalid value {} at row {}, column {}",
+                value + 1,
+                row + 1,
+                col + 1
+            ),
+            LoadingError::InvalidSize { received } => {
+                write!(f, "invalid grid size, received {received} cells")
+            }
+            LoadingError::Conflicting {
+                value,
+                first,
+                second,
+            } => write!(
+                f,
+                "value {} at row {}, column {} clashes with the one at row {}, column {}",
+                value + 1,
+                second.0 + 1,
+                second.1 + 1,
+                first.0 + 1,
+                first.1 + 1
+            ),
+            LoadingError::Contradiction { value, row, col } => write!(
+                f,
+                "value {} at row {}, column {} contradicts the other givens",
+                value + 1,
+                row + 1,
+                col + 1
+            ),
+        }
+    }
 }
 
-pub trait Choose<const N: usize> {
+impl std::error::Error for LoadingError {}
+
+pub trait Choose<const N: usize>
+where
+    Size<N>: Storage,
+{
     fn choose_value_in_cell(&mut self, cell: Cell<N>) -> Option<u32>;
     fn choose_pop_value_in_cell(&mut self, cell: &mut Cell<N>) -> Option<u32> {
         let value = self.choose_value_in_cell(*cell)?;
@@ -495,12 +695,18 @@ pub trait Choose<const N: usize> {
     }
 }
 
-impl<const N: usize> Choose<N> for SmallRng {
+impl<const N: usize> Choose<N> for SmallRng
+where
+    Size<N>: Storage,
+{
     fn choose_value_in_cell(&mut self, cell: Cell<N>) -> Option<u32> {
         cell.choose(self)
     }
 }
-impl<const N: usize> Choose<N> for () {
+impl<const N: usize> Choose<N> for ()
+where
+    Size<N>: Storage,
+{
     fn choose_value_in_cell(&mut self, cell: Cell<N>) -> Option<u32> {
         cell.first()
     }
@@ -513,9 +719,55 @@ pub trait RngChild: Rng + SeedableRng {
 }
 impl RngChild for SmallRng {}
 
+// `separator` is the left end, the right end, the thin and the bold crossings
+fn print_line_sep(
+    mut writer: impl Write,
+    n: usize,
+    separator: [char; 4],
+    line: &str,
+) -> Result<(), std::io::Error> {
+    let [left, right, cross_thin, cross_bold] = separator;
+    let nn = n * n;
+    write!(writer, "{left}{line}")?;
+    for x in 1..nn {
+        if x % n == 0 {
+            write!(writer, "{cross_bold}{line}")?;
+        } else {
+            write!(writer, "{cross_thin}{line}")?;
+        }
+    }
+    writeln!(writer, "{right}")?;
+    Ok(())
+}
+
 pub const fn mask_full<const N: usize>() -> [[[[bool; N]; N]; N]; N] {
     [[[[true; N]; N]; N]; N]
 }
 pub const fn mask_empty<const N: usize>() -> [[[[bool; N]; N]; N]; N] {
     [[[[false; N]; N]; N]; N]
 }
+
+#[test]
+fn candidate_view() {
+    let grid = Sudoku::<2>::from_numbers("1 0 0 0\n0 0 0 0\n0 0 0 0\n0 0 0 0").unwrap();
+    let mut printed = Vec::new();
+    grid.print_candidates(&mut printed, &Alphabet::standard(), Style::plain())
+        .unwrap();
+    let printed = String::from_utf8(printed).unwrap();
+    let lines: Vec<&str> = printed.lines().collect();
+    // a border, then two lines per row of cells and a separator
+    assert_eq!(lines.len(), 1 + 4 * 3);
+    assert_eq!(lines[0], "┏━━━━━┯━━━━━┳━━━━━┯━━━━━┓");
+    assert_eq!(lines[1], "┃     │   2 ┃   2 │   2 ┃");
+    assert_eq!(lines[2], "┃   1 │ 3 4 ┃ 3 4 │ 3 4 ┃");
+    assert_eq!(lines[4], "┃   2 │   2 ┃ 1 2 │ 1 2 ┃");
+}
+
+#[test]
+fn shuffled_pattern() {
+    let mut rng = SmallRng::seed_from_u64(3);
+    assert!(Sudoku::<3>::pattern().is_accepting());
+    let grid = Sudoku::<3>::generate_shuffled(&mut rng);
+    assert!(grid.is_accepting());
+    assert!(Sudoku::<5>::generate_shuffled(&mut rng).is_accepting());
+}
@@ -0,0 +1,677 @@
+use crate::{Alphabet, Cell, Defer, House, LoadingError, Pos, Size, Storage, Sudoku, peers};
+
+/// A deduction a person can make on the pencil marks, the easiest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Technique {
+    /// A cell with a single candidate left
+    NakedSingle,
+    /// A value with a single place left in a house
+    HiddenSingle,
+    /// The places of a value in a square all lie in one line,
+    /// which can't hold it elsewhere
+    Pointing,
+    /// The places of a value in a line all lie in one square,
+    /// which can't hold it elsewhere
+    Claiming,
+    /// Two cells of a house with the same two candidates
+    NakedPair,
+    /// Three cells of a house with three candidates between them
+    NakedTriple,
+    /// Two values with the same two places in a house
+    HiddenPair,
+    /// Three values with three places between them in a house
+    HiddenTriple,
+    /// The places of a value in two rows lie in the same two columns, or the other way
+    XWing,
+}
+
+impl Technique {
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "naked single",
+            Technique::HiddenSingle => "hidden single",
+            Technique::Pointing => "pointing",
+            Technique::Claiming => "claiming",
+            Technique::NakedPair => "naked pair",
+            Technique::NakedTriple => "naked triple",
+            Technique::HiddenPair => "hidden pair",
+            Technique::HiddenTriple => "hidden triple",
+            Technique::XWing => "X-wing",
+        }
+    }
+
+    /// The difficulty of a puzzle needing this technique
+    pub fn difficulty(self) -> Difficulty {
+        match self {
+            Technique::NakedSingle | Technique::HiddenSingle => Difficulty::Easy,
+            Technique::Pointing | Technique::Claiming => Difficulty::Medium,
+            Technique::NakedPair
+            | Technique::NakedTriple
+            | Technique::HiddenPair
+            | Technique::HiddenTriple => Difficulty::Hard,
+            Technique::XWing => Difficulty::Expert,
+        }
+    }
+}
+
+impl std::fmt::Display for Technique {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How hard a puzzle is, after the hardest technique it needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+    /// The techniques are not enough, the solver must guess
+    Guessing,
+}
+
+impl std::fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Expert => "expert",
+            Difficulty::Guessing => "beyond the techniques, needs guessing",
+        })
+    }
+}
+
+/// One deduction of the [Logic] solver
+///
+/// Cells are given as rows and columns, counted from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    /// The houses the deduction is made in
+    pub houses: Vec<House>,
+    /// The cells the deduction is drawn from
+    pub cells: Vec<(usize, usize)>,
+    /// The values the deduction is about
+    pub values: Vec<u32>,
+    /// The value placed, for the singles
+    pub placed: Option<((usize, usize), u32)>,
+    /// The candidates ruled out, for a single the value in the peers of its cell
+    pub removed: Vec<((usize, usize), u32)>,
+}
+
+impl Step {
+    /// The step with the values written in the symbols of `alphabet`,
+    /// the way the grid is printed
+    ///
+    /// Values past the end of the alphabet are written as numbers from 1.
+    pub fn display<'a>(&'a self, alphabet: &'a Alphabet) -> StepDisplay<'a> {
+        StepDisplay {
+            step: self,
+            alphabet,
+        }
+    }
+}
+
+/// A [Step] written with an alphabet, see [Step::display]
+pub struct StepDisplay<'a> {
+    step: &'a Step,
+    alphabet: &'a Alphabet,
+}
+
+/// Written with [Alphabet::standard], see [Step::display]
+impl std::fmt::Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.display(&Alphabet::standard()).fmt(f)
+    }
+}
+
+impl std::fmt::Display for StepDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let step = self.step;
+        let cell = |(row, col): (usize, usize)| format!("r{}c{}", row + 1, col + 1);
+        let symbol = |value: u32| match self.alphabet.symbol(value) {
+            Some(symbol) => symbol.to_string(),
+            None => (value + 1).to_string(),
+        };
+        let join = |items: Vec<String>| items.join(" ");
+        write!(f, "{}", step.technique)?;
+        if !step.houses.is_empty() {
+            let houses: Vec<String> = step.houses.iter().map(House::to_string).collect();
+            write!(f, " in {}", houses.join(" and "))?;
+        }
+        if let Some((pos, value)) = step.placed {
+            return write!(f, ": {} = {}", cell(pos), symbol(value));
+        }
+        let values = step.values.iter().map(|value| symbol(*value));
+        let cells = step.cells.iter().map(|pos| cell(*pos));
+        write!(
+            f,
+            ": {} at {}",
+            join(values.collect()),
+            join(cells.collect())
+        )?;
+        let mut values: Vec<u32> = step.removed.iter().map(|(_, value)| *value).collect();
+        values.sort_unstable();
+        values.dedup();
+        for value in values {
+            let cells = step
+                .removed
+                .iter()
+                .filter(|(_, removed)| *removed == value)
+                .map(|(pos, _)| cell(*pos));
+            write!(
+                f,
+                ", removes {} from {}",
+                symbol(value),
+                join(cells.collect())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A summary of a logical solve, see [Logic::grade]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grade {
+    /// How many times each technique was used, the easiest first
+    pub techniques: Vec<(Technique, usize)>,
+    pub steps: usize,
+    /// Whether the techniques were enough to fill the grid
+    pub solved: bool,
+}
+
+impl Grade {
+    pub(crate) fn record(&mut self, step: &Step) {
+        self.steps += 1;
+        match self
+            .techniques
+            .binary_search_by_key(&step.technique, |(technique, _)| *technique)
+        {
+            Ok(i) => self.techniques[i].1 += 1,
+            Err(i) => self.techniques.insert(i, (step.technique, 1)),
+        }
+    }
+
+    /// The hardest technique used
+    pub fn hardest(&self) -> Option<Technique> {
+        self.techniques.last().map(|(technique, _)| *technique)
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        match self.hardest() {
+            _ if !self.solved => Difficulty::Guessing,
+            Some(technique) => technique.difficulty(),
+            None => Difficulty::Easy,
+        }
+    }
+}
+
+/// A solver applying the [Technique]s one step at a time, like a person would
+///
+/// Unlike the [Sudoku] it works on, candidates are only ruled out by the
+/// steps, the givens ruling out their value in their peers beforehand.
+#[derive(Debug, Clone)]
+pub struct Logic<const N: usize>
+where
+    Size<N>: Storage,
+{
+    grid: Sudoku<N>,
+    /// The cells whose value was ruled out of their peers
+    placed: [[[[bool; N]; N]; N]; N],
+    defer: Defer<N>,
+    /// A step would have left a cell without candidates
+    broken: bool,
+}
+
+impl<const N: usize> Logic<N>
+where
+    Size<N>: Storage,
+{
+    /// Start from the givens, one value per cell in the order of [Pos::iter]
+    ///
+    /// It fails like [Sudoku::from_values].
+    pub fn from_values(values: &[Option<u32>]) -> Result<Self, LoadingError> {
+        Sudoku::<N>::from_values(values)?;
+        let mut logic = Self {
+            grid: Sudoku::default(),
+            placed: [[[[false; N]; N]; N]; N],
+            defer: Defer::new(),
+            broken: false,
+        };
+        for (pos, value) in Pos::iter::<N>().zip(values) {
+            if let Some(value) = *value {
+                let removed = logic.placement(pos, value);
+                logic.apply(&removed);
+                logic.placed[pos] = true;
+            }
+        }
+        Ok(logic)
+    }
+
+    /// The candidates left by the givens and the steps so far
+    pub fn grid(&self) -> &Sudoku<N> {
+        &self.grid
+    }
+
+    /// Every cell holds a value
+    pub fn is_solved(&self) -> bool {
+        Pos::iter::<N>().all(|pos| self.placed[pos])
+    }
+
+    /// Make the easiest deduction available
+    ///
+    /// It returns `None` when the grid is solved, or when no technique applies.
+    pub fn step(&mut self) -> Option<Step> {
+        if self.broken {
+            return None;
+        }
+        let step = self
+            .single()
+            .or_else(|| self.hidden_single())
+            .or_else(|| self.locked())
+            .or_else(|| self.naked_subset(2))
+            .or_else(|| self.naked_subset(3))
+            .or_else(|| self.hidden_subset(2))
+            .or_else(|| self.hidden_subset(3))
+            .or_else(|| self.x_wing())?;
+        let removed: Vec<(Pos, u32)> = step
+            .removed
+            .iter()
+            .map(|&((row, col), value)| (Pos::from_row_col::<N>(row, col).unwrap(), value))
+            .collect();
+        let emptied = removed.iter().any(|&(pos, _)| {
+            let left = removed
+                .iter()
+                .filter(|(other, _)| *other == pos)
+                .fold(self.grid[pos], |cell, &(_, value)| cell - value);
+            left.is_empty()
+        });
+        if emptied {
+            self.broken = true;
+            return None;
+        }
+        self.apply(&removed);
+        if let Some(((row, col), _)) = step.placed {
+            self.placed[Pos::from_row_col::<N>(row, col).unwrap()] = true;
+        }
+        Some(step)
+    }
+
+    /// Take every step, and sum them up
+    pub fn grade(mut self) -> Grade {
+        let mut grade = Grade::default();
+        while let Some(step) = self.step() {
+            grade.record(&step);
+        }
+        grade.solved = self.is_solved();
+        grade
+    }
+
+    fn apply(&mut self, removed: &[(Pos, u32)]) {
+        let mut pushed = 0;
+        for &(pos, value) in removed {
+            self.grid
+                .remove_one(value, pos, &mut pushed, &mut self.defer);
+        }
+        self.defer.clear();
+    }
+
+    // The other candidates of the cell, and the value in its peers
+    fn placement(&self, pos: Pos, value: u32) -> Vec<(Pos, u32)> {
+        let others = (self.grid[pos] - value).map(|other| (pos, other));
+        let peers = peers::<N>(pos)
+            .filter(|peer| !self.placed[*peer] && self.grid[*peer].contains(value))
+            .map(|peer| (peer, value));
+        others.chain(peers).collect()
+    }
+
+    fn placing(&self, technique: Technique, houses: Vec<House>, pos: Pos, value: u32) -> Step {
+        Step {
+            technique,
+            houses,
+            cells: vec![pos.row_col::<N>()],
+            values: vec![value],
+            placed: Some((pos.row_col::<N>(), value)),
+            removed: rows_cols::<N>(self.placement(pos, value)),
+        }
+    }
+
+    fn single(&self) -> Option<Step> {
+        let pos = Pos::iter::<N>().find(|pos| !self.placed[*pos] && self.grid[*pos].len() == 1)?;
+        let value = self.grid[pos].get_value()?;
+        Some(self.placing(Technique::NakedSingle, Vec::new(), pos, value))
+    }
+
+    // The open cells of the house holding `value`
+    fn places(&self, house: House, value: u32) -> Vec<Pos> {
+        house
+            .cells::<N>()
+            .filter(|pos| !self.placed[*pos] && self.grid[*pos].contains(value))
+            .collect()
+    }
+
+    // Squares first, where hidden singles are the easiest to spot
+    fn houses() -> impl Iterator<Item = House> {
+        let nn = N * N;
+        (0..nn)
+            .map(House::Box)
+            .chain((0..nn).map(House::Row))
+            .chain((0..nn).map(House::Col))
+    }
+
+    fn is_placed_in(&self, house: House, value: u32) -> bool {
+        house
+            .cells::<N>()
+            .any(|pos| self.placed[pos] && self.grid[pos].contains(value))
+    }
+
+    fn hidden_single(&self) -> Option<Step> {
+        for house in Self::houses() {
+            for value in 0..Cell::<N>::R {
+                if let [pos] = self.places(house, value)[..] {
+                    return Some(self.placing(Technique::HiddenSingle, vec![house], pos, value));
+                }
+            }
+        }
+        None
+    }
+
+    // Pointing and claiming: the places of a value in one house all lie in another
+    fn locked(&self) -> Option<Step> {
+        let nn = N * N;
+        let lines = || (0..nn).map(House::Row).chain((0..nn).map(House::Col));
+        let pointing = (0..nn).flat_map(|i| lines().map(move |line| (House::Box(i), line)));
+        let claiming = lines().flat_map(|line| (0..nn).map(move |i| (line, House::Box(i))));
+        for (technique, (house, other)) in pointing
+            .map(|pair| (Technique::Pointing, pair))
+            .chain(claiming.map(|pair| (Technique::Claiming, pair)))
+        {
+            for value in 0..Cell::<N>::R {
+                let places = self.places(house, value);
+                if places.len() < 2 || !places.iter().all(|pos| other.contains::<N>(*pos)) {
+                    continue;
+                }
+                let removed: Vec<(Pos, u32)> = self
+                    .places(other, value)
+                    .into_iter()
+                    .filter(|pos| !house.contains::<N>(*pos))
+                    .map(|pos| (pos, value))
+                    .collect();
+                if !removed.is_empty() {
+                    return Some(Step {
+                        technique,
+                        houses: vec![house, other],
+                        cells: places.iter().map(|pos| pos.row_col::<N>()).collect(),
+                        values: vec![value],
+                        placed: None,
+                        removed: rows_cols::<N>(removed),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    // `k` cells of a house with `k` candidates between them
+    fn naked_subset(&self, k: usize) -> Option<Step> {
+        let technique = match k {
+            2 => Technique::NakedPair,
+            _ => Technique::NakedTriple,
+        };
+        for house in Self::houses() {
+            let open: Vec<Pos> = house
+                .cells::<N>()
+                .filter(|pos| !self.placed[*pos] && (2..=k).contains(&self.grid[*pos].len()))
+                .collect();
+            for subset in subsets(open.len(), k) {
+                let cells: Vec<Pos> = subset.iter().map(|&i| open[i]).collect();
+                let values = cells
+                    .iter()
+                    .fold(Cell::<N>::EMPTY, |acc, pos| acc | self.grid[*pos]);
+                if values.len() != k {
+                    continue;
+                }
+                let removed: Vec<(Pos, u32)> = house
+                    .cells::<N>()
+                    .filter(|pos| !self.placed[*pos] && !cells.contains(pos))
+                    .flat_map(|pos| (self.grid[pos] & values).map(move |value| (pos, value)))
+                    .collect();
+                if !removed.is_empty() {
+                    return Some(Step {
+                        technique,
+                        houses: vec![house],
+                        cells: cells.iter().map(|pos| pos.row_col::<N>()).collect(),
+                        values: values.collect(),
+                        placed: None,
+                        removed: rows_cols::<N>(removed),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    // `k` values of a house with `k` places between them
+    fn hidden_subset(&self, k: usize) -> Option<Step> {
+        let technique = match k {
+            2 => Technique::HiddenPair,
+            _ => Technique::HiddenTriple,
+        };
+        for house in Self::houses() {
+            let open: Vec<(u32, Vec<Pos>)> = (0..Cell::<N>::R)
+                .filter(|value| !self.is_placed_in(house, *value))
+                .map(|value| (value, self.places(house, value)))
+                .filter(|(_, places)| (2..=k).contains(&places.len()))
+                .collect();
+            for subset in subsets(open.len(), k) {
+                let mut cells: Vec<Pos> = subset
+                    .iter()
+                    .flat_map(|&i| open[i].1.iter().copied())
+                    .collect();
+                cells.sort_unstable();
+                cells.dedup();
+                if cells.len() != k {
+                    continue;
+                }
+                let values = subset.iter().fold(Cell::<N>::EMPTY, |acc, &i| {
+                    acc | Cell::from_value(open[i].0)
+                });
+                let removed: Vec<(Pos, u32)> = cells
+                    .iter()
+                    .flat_map(|&pos| (self.grid[pos] & !values).map(move |value| (pos, value)))
+                    .collect();
+                if !removed.is_empty() {
+                    return Some(Step {
+                        technique,
+                        houses: vec![house],
+                        cells: cells.iter().map(|pos| pos.row_col::<N>()).collect(),
+                        values: values.collect(),
+                        placed: None,
+                        removed: rows_cols::<N>(removed),
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    fn x_wing(&self) -> Option<Step> {
+        let nn = N * N;
+        for rows in [true, false] {
+            let line = |i| if rows { House::Row(i) } else { House::Col(i) };
+            let other = |i| if rows { House::Col(i) } else { House::Row(i) };
+            let cross = |pos: Pos| if rows { pos.col::<N>() } else { pos.row::<N>() };
+            for value in 0..Cell::<N>::R {
+                let pairs: Vec<(usize, Vec<Pos>)> = (0..nn)
+                    .map(|i| (i, self.places(line(i), value)))
+                    .filter(|(_, places)| places.len() == 2)
+                    .collect();
+                for subset in subsets(pairs.len(), 2) {
+                    let (first, second) = (&pairs[subset[0]], &pairs[subset[1]]);
+                    let crossed: Vec<usize> = first.1.iter().map(|pos| cross(*pos)).collect();
+                    if crossed != second.1.iter().map(|pos| cross(*pos)).collect::<Vec<_>>() {
+                        continue;
+                    }
+                    let cells: Vec<Pos> = first.1.iter().chain(&second.1).copied().collect();
+                    let removed: Vec<(Pos, u32)> = crossed
+                        .iter()
+                        .flat_map(|&i| self.places(other(i), value))
+                        .filter(|pos| !cells.contains(pos))
+                        .map(|pos| (pos, value))
+                        .collect();
+                    if !removed.is_empty() {
+                        return Some(Step {
+                            technique: Technique::XWing,
+                            houses: vec![line(first.0), line(second.0)],
+                            cells: cells.iter().map(|pos| pos.row_col::<N>()).collect(),
+                            values: vec![value],
+                            placed: None,
+                            removed: rows_cols::<N>(removed),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+fn rows_cols<const N: usize>(removed: Vec<(Pos, u32)>) -> Vec<((usize, usize), u32)> {
+    removed
+        .into_iter()
+        .map(|(pos, value)| (pos.row_col::<N>(), value))
+        .collect()
+}
+
+// The subsets of `k` indices in `0..len`, in lexicographic order
+fn subsets(len: usize, k: usize) -> Subsets {
+    Subsets {
+        len,
+        next: (k <= len).then(|| (0..k).collect()),
+    }
+}
+
+// Iterator over the subsets of indices, see `subsets`
+struct Subsets {
+    len: usize,
+    next: Option<Vec<usize>>,
+}
+
+impl Iterator for Subsets {
+    type Item = Vec<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let subset = self.next.take()?;
+        let (len, k) = (self.len, subset.len());
+        // the last index that can still move right
+        if let Some(i) = (0..k).rev().find(|&i| subset[i] < len - k + i) {
+            let mut next = subset.clone();
+            next[i] += 1;
+            for j in i + 1..k {
+                next[j] = next[j - 1] + 1;
+            }
+            self.next = Some(next);
+        }
+        Some(subset)
+    }
+}
+
+#[test]
+fn easy_puzzle_by_singles() {
+    use crate::{Alphabet, Layout};
+
+    let puzzle =
+        "..3.2.6..9..3.5..1..18.64....81.29..7.......8..67.82....26.95..8..2.3..9..5.1.3..";
+    let values = Layout::Line.read(puzzle, &Alphabet::standard()).unwrap();
+    let mut logic = Logic::<3>::from_values(&values).unwrap();
+    let solution = Sudoku::<3>::from_values(&values)
+        .unwrap()
+        .brute_force((), 0..)
+        .next()
+        .unwrap();
+    let mut steps = 0;
+    while let Some(step) = logic.step() {
+        let ((row, col), value) = step.placed.unwrap();
+        let pos = Pos::from_row_col::<3>(row, col).unwrap();
+        assert_eq!(solution[pos], Cell::from_value(value));
+        steps += 1;
+    }
+    assert!(logic.is_solved());
+    assert_eq!(steps, 81 - 32);
+
+    let grade = Logic::<3>::from_values(&values).unwrap().grade();
+    assert!(grade.solved);
+    assert_eq!(grade.steps, 49);
+    assert_eq!(grade.difficulty(), Difficulty::Easy);
+}
+
+#[test]
+fn steps_keep_the_solution() {
+    use crate::{Alphabet, Layout};
+
+    let puzzles = [
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......",
+        "..53.....8......2..7..1.5..4....53...1..7...6..32...8..6.5....9..4....3......97..",
+        "85...24..72......9..4.........1.7..23.5...9...4...........8..7..17..........36.4.",
+    ];
+    let mut techniques = Vec::new();
+    for puzzle in puzzles {
+        let values = Layout::Line.read(puzzle, &Alphabet::standard()).unwrap();
+        let solution = Sudoku::<3>::from_values(&values)
+            .unwrap()
+            .brute_force((), 0..)
+            .next()
+            .unwrap();
+        let mut logic = Logic::<3>::from_values(&values).unwrap();
+        while let Some(step) = logic.step() {
+            for &((row, col), value) in &step.removed {
+                let pos = Pos::from_row_col::<3>(row, col).unwrap();
+                assert_ne!(solution[pos], Cell::from_value(value), "{step}");
+            }
+            techniques.push(step.technique);
+        }
+        assert!(
+            Pos::iter::<3>()
+                .all(|pos| logic.grid()[pos].contains(solution[pos].get_value().unwrap()))
+        );
+    }
+    assert!(
+        techniques
+            .iter()
+            .any(|technique| *technique > Technique::HiddenSingle)
+    );
+}
+
+#[test]
+fn step_display() {
+    assert_eq!(subsets(4, 2).count(), 6);
+    assert_eq!(subsets(2, 3).next(), None);
+    assert_eq!(subsets(3, 2).last(), Some(vec![1, 2]));
+    let step = Step {
+        technique: Technique::Pointing,
+        houses: vec![House::Box(0), House::Row(1)],
+        cells: vec![(1, 0), (1, 2)],
+        values: vec![4],
+        placed: None,
+        removed: vec![((1, 5), 4), ((1, 8), 4)],
+    };
+    assert_eq!(
+        step.to_string(),
+        "pointing in box 1 and row 2: 5 at r2c1 r2c3, removes 5 from r2c6 r2c9"
+    );
+    let step = Step {
+        values: vec![9, 10],
+        removed: vec![((1, 5), 10)],
+        ..step
+    };
+    assert_eq!(
+        step.display(&Alphabet::hex()).to_string(),
+        "pointing in box 1 and row 2: 9 A at r2c1 r2c3, removes A from r2c6"
+    );
+    assert_eq!(
+        step.display(&Alphabet::letters()).to_string(),
+        "pointing in box 1 and row 2: J K at r2c1 r2c3, removes K from r2c6"
+    );
+}
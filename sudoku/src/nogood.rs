@@ -0,0 +1,522 @@
+use std::collections::VecDeque;
+
+use crate::{Cell, Defer, House, Pos, Size, Storage, Sudoku, peers};
+
+/// A fact about a cell the search relies on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Literal {
+    /// The cell holds only the value
+    Has(Pos, u32),
+    /// The value was removed from the cell
+    Lacks(Pos, u32),
+}
+
+impl Literal {
+    /// Does it hold on the grid, `None` if it may still go either way
+    fn holds<const N: usize>(self, grid: &Sudoku<N>) -> Option<bool>
+    where
+        Size<N>: Storage,
+    {
+        let (pos, value, has) = match self {
+            Literal::Has(pos, value) => (pos, value, true),
+            Literal::Lacks(pos, value) => (pos, value, false),
+        };
+        if !grid[pos].contains(value) {
+            Some(!has)
+        } else if grid[pos] == Cell::from_value(value) {
+            Some(has)
+        } else {
+            None
+        }
+    }
+}
+
+/// Why the propagation failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Conflict<const N: usize>
+where
+    Size<N>: Storage,
+{
+    /// The cell lost the values it had to keep
+    Last { pos: Pos, value: u32 },
+    /// Two cells of a house hold only the value
+    Twice { pos: Pos, other: Pos, value: u32 },
+    /// The values have no other place than the cell in one of its houses,
+    /// either several of them or one the cell lacks
+    Hidden { pos: Pos, values: Cell<N> },
+    /// Every literal of a learned nogood holds
+    Nogood(Vec<Literal>),
+}
+
+/// Bounded database of learned nogoods
+///
+/// A nogood is a set of literals that can't hold all together.
+/// Nogoods are only meaningful for the grid they were learned from.
+/// When the database is full, the oldest nogood is forgotten.
+#[derive(Debug, Clone)]
+pub(crate) struct Nogoods<const N: usize>
+where
+    Size<N>: Storage,
+{
+    learned: VecDeque<Vec<Literal>>,
+    capacity: usize,
+}
+
+impl<const N: usize> Nogoods<N>
+where
+    Size<N>: Storage,
+{
+    /// Longer nogoods are not worth learning, a few houses worth of cells
+    const LENGTH_MAX: usize = 8 * N * N;
+
+    /// A database remembering at most `capacity` nogoods
+    ///
+    /// A capacity of `0` disables learning.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            learned: VecDeque::new(),
+            capacity,
+        }
+    }
+    pub const fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    /// Remember a nogood, it must be sorted and without duplicates
+    pub fn learn(&mut self, nogood: Vec<Literal>) {
+        if !self.is_enabled() || nogood.len() > Self::LENGTH_MAX || self.learned.contains(&nogood) {
+            return;
+        }
+        if self.learned.len() == self.capacity {
+            self.learned.pop_front();
+        }
+        self.learned.push_back(nogood);
+    }
+
+    /// Enforce the learned nogoods on the grid until nothing changes
+    ///
+    /// When all literals of a nogood but one hold, the remaining one is
+    /// made false, with the usual cascading effect.
+    /// The pushed moves are counted in `pushed`, and left on the grid
+    /// on failure, like [Sudoku::remove_all_traced].
+    pub fn propagate(
+        &self,
+        grid: &mut Sudoku<N>,
+        pushed: &mut usize,
+        defer: &mut Defer<N>,
+    ) -> Result<(), Conflict<N>> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            'nogoods: for nogood in &self.learned {
+                let mut undecided = None;
+                for &literal in nogood {
+                    match literal.holds(grid) {
+                        Some(true) => {}
+                        Some(false) => continue 'nogoods,
+                        None if undecided.is_some() => continue 'nogoods,
+                        None => undecided = Some(literal),
+                    }
+                }
+                let (pos, values) = match undecided {
+                    None => return Err(Conflict::Nogood(nogood.clone())),
+                    Some(Literal::Has(pos, value)) => (pos, Cell::from_value(value)),
+                    Some(Literal::Lacks(pos, value)) => (pos, !Cell::from_value(value)),
+                };
+                grid.remove_all_traced(values, pos, pushed, defer)
+                    .map_err(|conflict| match conflict {
+                        // the remaining literal holds as well
+                        Conflict::Last { .. } => Conflict::Nogood(nogood.clone()),
+                        conflict => conflict,
+                    })?;
+                changed = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Finds the literals responsible for a conflict
+///
+/// Each move of the grid is explained by earlier ones: the decision of its
+/// cell, a peer holding the value, a value with no other place in a house,
+/// or a learned nogood. Following the explanations back from the conflict,
+/// through the moves of the last decision only, leads to literals which
+/// held before it. Nothing is replayed, so it costs a pass over the moves.
+pub(crate) struct Analysis<const N: usize>
+where
+    Size<N>: Storage,
+{
+    /// Moves pushed before the search
+    root: usize,
+    /// When each value of each cell was removed, `usize::MAX` if it wasn't
+    removed: Vec<usize>,
+    /// The value of each cell left with one, and when it was left alone
+    settled: Vec<Option<(u32, usize)>>,
+    /// Literals already followed
+    seen: Vec<bool>,
+    pending: Vec<Literal>,
+    followed: Vec<usize>,
+}
+
+impl<const N: usize> Analysis<N>
+where
+    Size<N>: Storage,
+{
+    const R: usize = N * N;
+
+    pub fn new(root: &Sudoku<N>) -> Self {
+        let cells = N * N * N * N;
+        Self {
+            root: root.move_count(),
+            removed: vec![usize::MAX; cells * Self::R],
+            settled: vec![None; cells],
+            seen: vec![false; 2 * cells * Self::R],
+            pending: Vec::new(),
+            followed: Vec::new(),
+        }
+    }
+
+    /// Number of moves pushed before the search
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    fn slot(pos: Pos, value: u32) -> usize {
+        pos.index::<N>() * Self::R + value as usize
+    }
+
+    /// Index the moves of the grid, before looking for reasons
+    pub fn index(&mut self, grid: &Sudoku<N>) {
+        self.removed.fill(usize::MAX);
+        for (i, &(value, pos)) in grid.moves_since(0).iter().enumerate() {
+            self.removed[Self::slot(pos, value)] = i;
+        }
+        for (pos, settled) in Pos::iter::<N>().zip(&mut self.settled) {
+            let cell = &self.removed[pos.index::<N>() * Self::R..][..Self::R];
+            *settled = grid[pos].get_value().map(|value| {
+                let when = (0..Self::R)
+                    .filter(|v| *v as u32 != value)
+                    .map(|v| cell[v])
+                    .max()
+                    .unwrap_or(0);
+                (value, when)
+            });
+        }
+    }
+
+    /// The move making the literal hold, `usize::MAX` if it doesn't
+    pub fn when(&self, literal: Literal) -> usize {
+        match literal {
+            Literal::Has(pos, value) => match self.settled[pos.index::<N>()] {
+                Some((settled, when)) if settled == value => when,
+                _ => usize::MAX,
+            },
+            Literal::Lacks(pos, value) => self.removed[Self::slot(pos, value)],
+        }
+    }
+
+    fn follow(&mut self, literal: Literal) {
+        let slot = match literal {
+            Literal::Has(pos, value) => Self::slot(pos, value),
+            Literal::Lacks(pos, value) => self.removed.len() + Self::slot(pos, value),
+        };
+        if !self.seen[slot] {
+            self.seen[slot] = true;
+            self.followed.push(slot);
+            self.pending.push(literal);
+        }
+    }
+
+    // The house where the value has no other place than the cell before the
+    // move, the one where it lost its last other place the earliest,
+    // with that move
+    fn hidden_in(&self, pos: Pos, value: u32, before: usize) -> Option<(House, usize)> {
+        Self::houses(pos)
+            .into_iter()
+            .filter_map(|house| {
+                let mut latest = 0;
+                for other in house.cells::<N>().filter(|other| *other != pos) {
+                    let when = self.removed[Self::slot(other, value)];
+                    if when >= before {
+                        return None;
+                    }
+                    latest = latest.max(when);
+                }
+                Some((house, latest))
+            })
+            .min_by_key(|(_, latest)| *latest)
+    }
+
+    // Follow the moves leaving the value no other place than the cell in the house
+    fn follow_hidden(&mut self, pos: Pos, value: u32, house: House) {
+        for other in house.cells::<N>().filter(|other| *other != pos) {
+            self.follow(Literal::Lacks(other, value));
+        }
+    }
+
+    fn houses(pos: Pos) -> [House; 3] {
+        [
+            House::Row(pos.row::<N>()),
+            House::Col(pos.col::<N>()),
+            House::Box(pos.box_index::<N>()),
+        ]
+    }
+
+    // Follow the literals which removed the value from the cell, `false` if none does
+    //
+    // The earliest explanation is preferred, so that the literals found
+    // hold since as few levels as possible.
+    fn explain_move(&mut self, pos: Pos, value: u32, nogoods: &Nogoods<N>) -> bool {
+        let when = self.removed[Self::slot(pos, value)];
+        // a peer already held the value
+        let holder = peers::<N>(pos)
+            .filter_map(|other| match self.settled[other.index::<N>()] {
+                Some((v, t)) if v == value && t < when => Some((t, other)),
+                _ => None,
+            })
+            .min();
+        // another value had no other place than the cell
+        let hidden = (0..Self::R as u32)
+            .filter(|other| *other != value && self.removed[Self::slot(pos, *other)] > when)
+            .filter_map(|other| {
+                let (house, latest) = self.hidden_in(pos, other, when)?;
+                Some((latest, other, house))
+            })
+            .min_by_key(|(latest, ..)| *latest);
+        match (holder, hidden) {
+            (Some((t, holder)), hidden) if hidden.is_none_or(|(latest, ..)| t <= latest) => {
+                self.follow(Literal::Has(holder, value));
+                return true;
+            }
+            (_, Some((_, other, house))) => {
+                self.follow_hidden(pos, other, house);
+                return true;
+            }
+            _ => {}
+        }
+        // the other literals of a nogood already held
+        let forcing = |literal: &Literal| match *literal {
+            Literal::Has(p, v) => p == pos && v == value,
+            Literal::Lacks(p, v) => p == pos && v != value,
+        };
+        let nogood = nogoods.learned.iter().find(|nogood| {
+            nogood.iter().any(forcing)
+                && nogood
+                    .iter()
+                    .filter(|literal| !forcing(literal))
+                    .all(|literal| self.when(*literal) < when)
+        });
+        if let Some(nogood) = nogood {
+            for &literal in nogood.iter().filter(|literal| !forcing(literal)) {
+                self.follow(literal);
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Replace the literals made to hold by the moves since `start` with
+    /// the ones they follow from, up to the `decision` starting them
+    ///
+    /// Literals holding before the search are dropped, they always do.
+    /// `None` if a move can't be explained.
+    pub fn cut(
+        &mut self,
+        literals: impl IntoIterator<Item = Literal>,
+        start: usize,
+        decision: (Pos, u32),
+        nogoods: &Nogoods<N>,
+    ) -> Option<Vec<Literal>> {
+        for literal in literals {
+            self.follow(literal);
+        }
+        let mut found = Vec::new();
+        let mut explained = true;
+        while let Some(literal) = self.pending.pop() {
+            let when = self.when(literal);
+            if when == usize::MAX {
+                explained = false;
+                break;
+            }
+            if when < self.root {
+                continue;
+            }
+            if when < start {
+                found.push(literal);
+                continue;
+            }
+            match literal {
+                // the decision holds the cell
+                Literal::Has(pos, value) if (pos, value) == decision => {}
+                Literal::Lacks(pos, value) if pos == decision.0 && value != decision.1 => {}
+                Literal::Has(pos, value) => {
+                    for other in (0..Self::R as u32).filter(|other| *other != value) {
+                        self.follow(Literal::Lacks(pos, other));
+                    }
+                }
+                Literal::Lacks(pos, value) => {
+                    if !self.explain_move(pos, value, nogoods) {
+                        explained = false;
+                        break;
+                    }
+                }
+            }
+        }
+        self.pending.clear();
+        for slot in self.followed.drain(..) {
+            self.seen[slot] = false;
+        }
+        found.sort_unstable();
+        explained.then_some(found)
+    }
+
+    /// The literals refuting the `decision` which led to a conflict,
+    /// `start` being the number of moves on the grid before it
+    pub fn explain(
+        &mut self,
+        grid: &Sudoku<N>,
+        conflict: &Conflict<N>,
+        start: usize,
+        decision: (Pos, u32),
+        nogoods: &Nogoods<N>,
+    ) -> Option<Vec<Literal>> {
+        self.index(grid);
+        let literals = match conflict {
+            // the decided value was removed from its cell
+            &Conflict::Last { .. } => vec![Literal::Lacks(decision.0, decision.1)],
+            &Conflict::Twice { pos, other, value } => {
+                vec![Literal::Has(pos, value), Literal::Has(other, value)]
+            }
+            &Conflict::Hidden { pos, values } => {
+                let mut literals = Vec::new();
+                for value in values {
+                    let (house, _) = self.hidden_in(pos, value, usize::MAX)?;
+                    literals.extend(
+                        house
+                            .cells::<N>()
+                            .filter(|other| *other != pos)
+                            .map(|other| Literal::Lacks(other, value)),
+                    );
+                    if !grid[pos].contains(value) {
+                        literals.push(Literal::Lacks(pos, value));
+                    }
+                }
+                literals
+            }
+            Conflict::Nogood(nogood) => nogood.clone(),
+        };
+        self.cut(literals, start, decision, nogoods)
+    }
+
+    /// The literals refuting every value of the cell at `pos`, a nogood
+    ///
+    /// `refuted` refutes the values of `cell`, the ones the cell had when
+    /// it was chosen, and the literals leaving it only those are added.
+    /// Leaves the grid indexed for [Analysis::cut].
+    pub fn restrict(
+        &mut self,
+        grid: &Sudoku<N>,
+        pos: Pos,
+        cell: Cell<N>,
+        mut refuted: Vec<Literal>,
+    ) -> Vec<Literal> {
+        self.index(grid);
+        refuted.extend(
+            (!cell)
+                .into_iter()
+                .map(|value| Literal::Lacks(pos, value))
+                .filter(|literal| self.when(*literal) >= self.root),
+        );
+        refuted.sort_unstable();
+        refuted.dedup();
+        refuted
+    }
+}
+
+/// Add the literals refuting a value to the ones refuting a level
+pub(crate) fn merge_refutation(refuted: &mut Option<Vec<Literal>>, literals: Option<&[Literal]>) {
+    match (refuted.as_mut(), literals) {
+        (Some(refuted), Some(literals)) => {
+            refuted.extend_from_slice(literals);
+            refuted.sort_unstable();
+            refuted.dedup();
+        }
+        _ => *refuted = None,
+    }
+}
+
+#[test]
+fn learning_finds_same_solutions() {
+    use crate::Alphabet;
+
+    let puzzle = "\
+        8________\
+        __36_____\
+        _7__9_2__\
+        _5___7___\
+        ____457__\
+        ___1___3_\
+        __1____68\
+        __85___1_\
+        _9____4__";
+    let src: Vec<u8> = puzzle
+        .chars()
+        .map(|c| Alphabet::standard().value(c).map_or(255, |v| v as u8))
+        .collect();
+    let mut plain = Sudoku::<3>::decode_grid(&src).unwrap();
+    let mut learning = plain.clone();
+    let expected: Vec<_> = plain.brute_force((), std::iter::repeat(0)).collect();
+    let learned: Vec<_> = learning
+        .brute_force_learning((), std::iter::repeat(0), 64)
+        .collect();
+    assert_eq!(expected.len(), 1);
+    assert_eq!(learned.len(), 1);
+    for pos in Pos::iter::<3>() {
+        assert_eq!(expected[0][pos], learned[0][pos]);
+    }
+}
+
+#[test]
+fn learning_enumerates_all_grids() {
+    let mut grid = Sudoku::<2>::default();
+    let count = grid
+        .brute_force_learning((), std::iter::repeat(0), 64)
+        .count();
+    assert_eq!(count, 288);
+}
+
+#[test]
+fn learning_saves_steps() {
+    use crate::Alphabet;
+
+    // Steps taken to prove the solution unique, as counted by the ttl
+    fn steps(puzzle: &Sudoku<3>, capacity: usize) -> usize {
+        let count = std::cell::Cell::new(0);
+        let ttl = std::iter::repeat(0).inspect(|_| count.set(count.get() + 1));
+        let found = puzzle
+            .clone()
+            .brute_force_learning((), ttl, capacity)
+            .count();
+        assert_eq!(found, 1);
+        count.get()
+    }
+
+    let puzzles = [
+        "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+        "1....7.9..3..2...8..96..5....53..9...1..8...26....4...3......1..4......7..7...3..",
+        ".2.4.37.........32........4.4.2...7.8...5.........1...5.....9...3.9....7..1..86..",
+    ];
+    let (mut plain, mut learning) = (0, 0);
+    for puzzle in puzzles {
+        let puzzle = Sudoku::<3>::from_line(puzzle, &Alphabet::standard()).unwrap();
+        let (a, b) = (steps(&puzzle, 0), steps(&puzzle, 64));
+        assert!(b <= a, "{b} steps with learning, {a} without");
+        plain += a;
+        learning += b;
+    }
+    assert!(
+        learning < plain,
+        "{learning} steps with learning, {plain} without"
+    );
+}
@@ -0,0 +1,354 @@
+use std::io::Write;
+
+use crate::{Alphabet, AnySudoku, Pos, Size, Storage, Sudoku};
+
+/// A printable booklet of puzzles, followed by their answers
+///
+/// It is written as a PDF, with A4 pages and the standard Helvetica font.
+#[derive(Debug, Clone)]
+pub struct Booklet {
+    title: String,
+    per_page: usize,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    title: String,
+    difficulty: String,
+    size: usize,
+    values: Vec<Option<u32>>,
+    givens: Vec<bool>,
+}
+
+const WIDTH: f64 = 595.0;
+const HEIGHT: f64 = 842.0;
+const MARGIN: f64 = 50.0;
+// Room for the title and difficulty above each grid
+const CAPTION: f64 = 28.0;
+
+// Where a grid goes on its page
+struct Slot {
+    x: f64,
+    y: f64,
+    side: f64,
+}
+
+// `count` slots on a page, in `cols` columns, below a header of `top` points
+fn slots(count: usize, cols: usize, top: f64) -> Vec<Slot> {
+    let rows = count.div_ceil(cols);
+    let (width, height) = (
+        (WIDTH - 2.0 * MARGIN) / cols as f64,
+        (HEIGHT - 2.0 * MARGIN - top) / rows as f64,
+    );
+    let side = (width - 20.0).min(height - CAPTION - 10.0);
+    (0..count)
+        .map(|i| {
+            let (col, row) = (i % cols, i / cols);
+            Slot {
+                x: MARGIN + col as f64 * width + (width - side) / 2.0,
+                y: HEIGHT - MARGIN - top - row as f64 * height - CAPTION - side,
+                side,
+            }
+        })
+        .collect()
+}
+
+// Strings are restricted to ASCII, the only characters sure to be in the font
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '(' | ')' | '\\' => format!("\\{c}"),
+            c if c.is_ascii() && !c.is_ascii_control() => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+// An estimate of the width of Helvetica text, its digits take 0.556 em
+fn text_width(text: &str, size: f64) -> f64 {
+    text.chars().count() as f64 * size * 0.556
+}
+
+// Drawing operators of a page
+#[derive(Default)]
+struct Page {
+    content: String,
+}
+
+impl Page {
+    fn text(&mut self, font: &str, size: f64, x: f64, y: f64, text: &str) {
+        self.content += &format!(
+            "BT /{font} {size:.1} Tf {x:.1} {y:.1} Td ({}) Tj ET\n",
+            escape(text)
+        );
+    }
+
+    fn centered(&mut self, font: &str, size: f64, x: f64, y: f64, text: &str) {
+        self.text(font, size, x - text_width(text, size) / 2.0, y, text);
+    }
+
+    fn line(&mut self, width: f64, from: (f64, f64), to: (f64, f64)) {
+        self.content += &format!(
+            "{width:.2} w {:.1} {:.1} m {:.1} {:.1} l S\n",
+            from.0, from.1, to.0, to.1
+        );
+    }
+
+    // The grid of an entry, with only its givens when it is a puzzle
+    fn grid(&mut self, entry: &Entry, slot: &Slot, answer: bool, alphabet: &Alphabet) {
+        let (n, nn) = (entry.size, entry.size * entry.size);
+        let cell = slot.side / nn as f64;
+        let top = slot.y + slot.side;
+        for i in 0..=nn {
+            let width = if i % n == 0 { 2.0 } else { 0.5 } * (cell / 20.0).min(1.0);
+            let at = i as f64 * cell;
+            self.line(width, (slot.x + at, slot.y), (slot.x + at, top));
+            self.line(
+                width,
+                (slot.x, slot.y + at),
+                (slot.x + slot.side, slot.y + at),
+            );
+        }
+        let ascii = alphabet.covers(n)
+            && (0..nn as u32).all(|value| alphabet.symbol(value).is_some_and(|c| c.is_ascii()));
+        for (i, (value, given)) in entry.values.iter().zip(&entry.givens).enumerate() {
+            let Some(value) = value.filter(|_| answer || *given) else {
+                continue;
+            };
+            let text = match ascii {
+                true => alphabet.symbol(value).unwrap().to_string(),
+                false => (value + 1).to_string(),
+            };
+            let size = cell * 0.6 / (1.0 + (text.len() as f64 - 1.0) * 0.5);
+            let font = if answer && !given { "F1" } else { "F2" };
+            let (x, y) = (
+                slot.x + ((i % nn) as f64 + 0.5) * cell,
+                top - ((i / nn) as f64 + 0.5) * cell - size * 0.35,
+            );
+            self.centered(font, size, x, y, &text);
+        }
+    }
+}
+
+impl Booklet {
+    /// An empty booklet, with `per_page` puzzles on each page
+    pub fn new(title: &str, per_page: usize) -> Self {
+        Self {
+            title: title.to_string(),
+            per_page: per_page.max(1),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Add a puzzle, the givens of the mask of its `solution`
+    pub fn add<const N: usize>(
+        &mut self,
+        title: &str,
+        difficulty: &str,
+        solution: &Sudoku<N>,
+        mask: [[[[bool; N]; N]; N]; N],
+    ) where
+        Size<N>: Storage,
+    {
+        self.entries.push(Entry {
+            title: title.to_string(),
+            difficulty: difficulty.to_string(),
+            size: N,
+            values: Pos::iter::<N>()
+                .map(|pos| solution[pos].get_value())
+                .collect(),
+            givens: Pos::iter::<N>().map(|pos| mask[pos]).collect(),
+        });
+    }
+
+    /// See [Booklet::add]
+    pub fn add_any(&mut self, title: &str, difficulty: &str, solution: &AnySudoku, mask: &[bool]) {
+        self.entries.push(Entry {
+            title: title.to_string(),
+            difficulty: difficulty.to_string(),
+            size: solution.size(),
+            values: solution.values(),
+            givens: mask.to_vec(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn cols(per_page: usize) -> usize {
+        if per_page <= 2 { 1 } else { 2 }
+    }
+
+    // The puzzles pages, then the answers, four times as many per page
+    fn pages(&self, alphabet: &Alphabet) -> Vec<Page> {
+        let mut pages = Vec::new();
+        let header = 30.0;
+        for (index, chunk) in self.entries.chunks(self.per_page).enumerate() {
+            let mut page = Page::default();
+            if index == 0 {
+                page.centered("F2", 18.0, WIDTH / 2.0, HEIGHT - MARGIN - 10.0, &self.title);
+            }
+            let cols = Self::cols(self.per_page);
+            for (i, (entry, slot)) in chunk
+                .iter()
+                .zip(slots(self.per_page, cols, header))
+                .enumerate()
+            {
+                let number = index * self.per_page + i + 1;
+                let caption = slot.y + slot.side + 8.0;
+                page.text(
+                    "F2",
+                    11.0,
+                    slot.x,
+                    caption,
+                    &format!("{number}. {}", entry.title),
+                );
+                let width = text_width(&entry.difficulty, 9.0);
+                page.text(
+                    "F1",
+                    9.0,
+                    slot.x + slot.side - width,
+                    caption,
+                    &entry.difficulty,
+                );
+                page.grid(entry, &slot, false, alphabet);
+            }
+            pages.push(page);
+        }
+
+        let per_page = self.per_page * 4;
+        let cols = Self::cols(self.per_page) * 2;
+        for (index, chunk) in self.entries.chunks(per_page).enumerate() {
+            let mut page = Page::default();
+            if index == 0 {
+                page.centered("F2", 16.0, WIDTH / 2.0, HEIGHT - MARGIN - 10.0, "Answers");
+            }
+            for (i, (entry, slot)) in chunk.iter().zip(slots(per_page, cols, header)).enumerate() {
+                let number = index * per_page + i + 1;
+                page.text(
+                    "F2",
+                    8.0,
+                    slot.x,
+                    slot.y + slot.side + 5.0,
+                    &format!("{number}. {}", entry.title),
+                );
+                page.grid(entry, &slot, true, alphabet);
+            }
+            pages.push(page);
+        }
+
+        let count = pages.len();
+        for (index, page) in pages.iter_mut().enumerate() {
+            let number = format!("{} / {count}", index + 1);
+            page.centered("F1", 9.0, WIDTH / 2.0, MARGIN / 2.0, &number);
+        }
+        pages
+    }
+
+    /// Write the booklet as a PDF document
+    ///
+    /// Symbols outside of ASCII are not in the font, such grids are written with numbers.
+    pub fn write_pdf(
+        &self,
+        mut writer: impl Write,
+        alphabet: &Alphabet,
+    ) -> Result<(), std::io::Error> {
+        let pages = self.pages(alphabet);
+        // objects 1 and 2 are the catalog and the page tree, 3 and 4 the fonts,
+        // then each page is followed by its content
+        let kids: Vec<String> = (0..pages.len())
+            .map(|i| format!("{} 0 R", 5 + 2 * i))
+            .collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold >>".to_string(),
+        ];
+        for (i, page) in pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {WIDTH} {HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                6 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+
+        let mut offsets = Vec::with_capacity(objects.len());
+        let mut written = 0;
+        let header = "%PDF-1.4\n";
+        writer.write_all(header.as_bytes())?;
+        written += header.len();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(written);
+            let object = format!("{} 0 obj\n{object}\nendobj\n", i + 1);
+            writer.write_all(object.as_bytes())?;
+            written += object.len();
+        }
+        writeln!(writer, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1)?;
+        for offset in offsets {
+            writeln!(writer, "{offset:010} 00000 n ")?;
+        }
+        write!(
+            writer,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{written}\n%%EOF\n",
+            objects.len() + 1
+        )
+    }
+}
+
+#[test]
+fn booklet_pdf() {
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    let mut rng = SmallRng::seed_from_u64(3);
+    let mut booklet = Booklet::new("Sudoku (vol. 1)", 4);
+    for i in 0..5 {
+        let solution = Sudoku::<3>::generate_full(&mut rng).unwrap();
+        let mask = solution.obfuscate(&mut rng);
+        booklet.add(&format!("Puzzle {i}"), "easy", &solution, mask);
+    }
+    let solution = AnySudoku::generate_full(2, &mut rng, 0).unwrap();
+    booklet.add_any("Small", "tiny", &solution, &solution.obfuscate(&mut rng));
+    assert_eq!(booklet.len(), 6);
+
+    let mut pdf = Vec::new();
+    booklet.write_pdf(&mut pdf, &Alphabet::standard()).unwrap();
+    let pdf = String::from_utf8(pdf).unwrap();
+    assert!(pdf.starts_with("%PDF-1.4"));
+    assert!(pdf.ends_with("%%EOF\n"));
+    // two pages of puzzles, one of answers
+    assert!(pdf.contains("/Count 3"));
+    assert_eq!(pdf.matches("/Type /Page ").count(), 3);
+    assert!(pdf.contains("(Sudoku \\(vol. 1\\)) Tj"));
+    assert!(pdf.contains("(Answers) Tj"));
+    assert!(pdf.contains("(3 / 3) Tj"));
+
+    // the cross-reference table points at the objects
+    let xref = pdf.rfind("\nxref\n").unwrap() + 1;
+    let start: usize = pdf[pdf.rfind("startxref\n").unwrap() + 10..]
+        .lines()
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(start, xref);
+    for (i, line) in pdf[xref..].lines().skip(3).take(10).enumerate() {
+        let offset: usize = line[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(&format!("{} 0 obj", i + 1)));
+    }
+}
@@ -0,0 +1,208 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{Cell, PosIter, Size, Storage};
+
+/// The position of a cell in the grid
+///
+/// The grid is seen as `N` bands of `N` rows and `N` stacks of `N` columns.
+/// `Pos` does not know the size of the grid, so conversions from and to
+/// plain coordinates take `N` as a generic parameter, and are checked against it:
+/// [Pos::from_row_col] and its siblings return `None` outside of the grid, and
+/// [Pos::row_col] and its siblings panic, like indexing a grid with the position.
+/// For the same reason, `(usize, usize)` has no `TryFrom` or `From` conversion,
+/// which couldn't name `N`: [Pos::from_row_col] and [Pos::row_col] are them.
+///
+/// With serde, it is written as its four coordinates, since the row and the
+/// column depend on `N`: the row is `band * N + row_in_band`, the column
+/// `stack * N + col_in_stack`. Coordinates beyond the largest grid are refused,
+/// but a position read for one grid may still be outside of a smaller one:
+/// check it with [Pos::is_valid] before indexing, which panics otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "crate::serialize::PosFields")
+)]
+pub struct Pos {
+    /// Selects the stack (column chunk)
+    #[cfg_attr(feature = "serde", serde(rename = "stack"))]
+    pub(crate) x_1: u8,
+    /// Selects the column in the stack
+    #[cfg_attr(feature = "serde", serde(rename = "col_in_stack"))]
+    pub(crate) x_2: u8,
+    /// Selects the band (row chunk)
+    #[cfg_attr(feature = "serde", serde(rename = "band"))]
+    pub(crate) y_1: u8,
+    /// Selects the row in the band
+    #[cfg_attr(feature = "serde", serde(rename = "row_in_band"))]
+    pub(crate) y_2: u8,
+}
+
+impl Pos {
+    pub const fn iter<const N: usize>() -> PosIter<N> {
+        PosIter::new()
+    }
+
+    /// The cell at `row` and `col`, both in `0..N*N`
+    pub const fn from_row_col<const N: usize>(row: usize, col: usize) -> Option<Pos> {
+        if row >= N * N || col >= N * N {
+            return None;
+        }
+        Some(Pos {
+            y_1: (row / N) as u8,
+            y_2: (row % N) as u8,
+            x_1: (col / N) as u8,
+            x_2: (col % N) as u8,
+        })
+    }
+
+    /// The cell at `index` in the square `box_index`, both in `0..N*N`
+    ///
+    /// Squares and cells in a square are numbered row by row.
+    pub const fn from_box<const N: usize>(box_index: usize, index_in_box: usize) -> Option<Pos> {
+        if box_index >= N * N || index_in_box >= N * N {
+            return None;
+        }
+        Some(Pos {
+            y_1: (box_index / N) as u8,
+            x_1: (box_index % N) as u8,
+            y_2: (index_in_box / N) as u8,
+            x_2: (index_in_box % N) as u8,
+        })
+    }
+
+    /// The cell at `index` in `0..N*N*N*N`, in the order of [Pos::iter]
+    pub const fn from_index<const N: usize>(index: usize) -> Option<Pos> {
+        if index >= N * N * N * N {
+            return None;
+        }
+        Self::from_row_col::<N>(index / (N * N), index % (N * N))
+    }
+
+    /// Is this position inside a grid of size `N`
+    pub const fn is_valid<const N: usize>(self) -> bool {
+        let n = N as u8;
+        N <= u8::MAX as usize && self.x_1 < n && self.x_2 < n && self.y_1 < n && self.y_2 < n
+    }
+
+    // Panic if the position is outside of a grid of size `N`
+    #[inline]
+    const fn check<const N: usize>(self) {
+        assert!(self.is_valid::<N>(), "position outside of the grid");
+    }
+
+    /// The row, in `0..N*N`, panics outside of the grid
+    pub const fn row<const N: usize>(self) -> usize {
+        self.check::<N>();
+        self.y_1 as usize * N + self.y_2 as usize
+    }
+
+    /// The column, in `0..N*N`, panics outside of the grid
+    pub const fn col<const N: usize>(self) -> usize {
+        self.check::<N>();
+        self.x_1 as usize * N + self.x_2 as usize
+    }
+
+    /// The square, in `0..N*N`, numbered row by row, panics outside of the grid
+    pub const fn box_index<const N: usize>(self) -> usize {
+        self.check::<N>();
+        self.y_1 as usize * N + self.x_1 as usize
+    }
+
+    /// The cell in its square, in `0..N*N`, numbered row by row,
+    /// panics outside of the grid
+    pub const fn index_in_box<const N: usize>(self) -> usize {
+        self.check::<N>();
+        self.y_2 as usize * N + self.x_2 as usize
+    }
+
+    /// The cell in `0..N*N*N*N`, in the order of [Pos::iter], panics outside of the grid
+    pub const fn index<const N: usize>(self) -> usize {
+        self.row::<N>() * N * N + self.col::<N>()
+    }
+
+    /// The `(row, col)` coordinates, the inverse of [Pos::from_row_col]
+    pub const fn row_col<const N: usize>(self) -> (usize, usize) {
+        (self.row::<N>(), self.col::<N>())
+    }
+
+    /// Are both cells in the same row, column or square
+    #[cfg(test)]
+    pub(crate) fn is_correlated(self, other: Pos) -> bool {
+        (self.y_1 == other.y_1 && self.y_2 == other.y_2)
+            || (self.x_1 == other.x_1 && self.x_2 == other.x_2)
+            || (self.y_1 == other.y_1 && self.x_1 == other.x_1)
+    }
+}
+
+impl<const N: usize> Index<Pos> for [[[[Cell<N>; N]; N]; N]; N]
+where
+    Size<N>: Storage,
+{
+    type Output = Cell<N>;
+
+    #[inline]
+    fn index(&self, index: Pos) -> &Self::Output {
+        &self[index.y_1 as usize][index.y_2 as usize][index.x_1 as usize][index.x_2 as usize]
+    }
+}
+impl<const N: usize> IndexMut<Pos> for [[[[Cell<N>; N]; N]; N]; N]
+where
+    Size<N>: Storage,
+{
+    #[inline]
+    fn index_mut(&mut self, index: Pos) -> &mut Self::Output {
+        &mut self[index.y_1 as usize][index.y_2 as usize][index.x_1 as usize][index.x_2 as usize]
+    }
+}
+impl<const N: usize> Index<Pos> for [[[[bool; N]; N]; N]; N] {
+    type Output = bool;
+
+    #[inline]
+    fn index(&self, index: Pos) -> &Self::Output {
+        &self[index.y_1 as usize][index.y_2 as usize][index.x_1 as usize][index.x_2 as usize]
+    }
+}
+impl<const N: usize> IndexMut<Pos> for [[[[bool; N]; N]; N]; N] {
+    #[inline]
+    fn index_mut(&mut self, index: Pos) -> &mut Self::Output {
+        &mut self[index.y_1 as usize][index.y_2 as usize][index.x_1 as usize][index.x_2 as usize]
+    }
+}
+
+#[test]
+fn coordinates() {
+    for (i, pos) in Pos::iter::<3>().enumerate() {
+        let (row, col) = pos.row_col::<3>();
+        assert_eq!(pos.index::<3>(), i);
+        assert_eq!((row, col), (i / 9, i % 9));
+        assert_eq!(Pos::from_row_col::<3>(row, col), Some(pos));
+        assert_eq!(Pos::from_index::<3>(i), Some(pos));
+        assert_eq!(pos.box_index::<3>(), row / 3 * 3 + col / 3);
+        assert_eq!(pos.index_in_box::<3>(), row % 3 * 3 + col % 3);
+        assert_eq!(
+            Pos::from_box::<3>(pos.box_index::<3>(), pos.index_in_box::<3>()),
+            Some(pos)
+        );
+    }
+    assert_eq!(Pos::from_row_col::<3>(9, 0), None);
+    assert_eq!(Pos::from_row_col::<3>(0, 9), None);
+    assert_eq!(Pos::from_box::<3>(9, 0), None);
+    assert_eq!(Pos::from_index::<3>(81), None);
+    assert!(Pos::from_row_col::<4>(15, 15).unwrap().is_valid::<4>());
+    assert!(!Pos::from_row_col::<4>(15, 15).unwrap().is_valid::<3>());
+}
+
+#[test]
+#[should_panic]
+fn outside_of_grid() {
+    let far = Pos::from_row_col::<11>(120, 120).unwrap();
+    let _ = crate::Sudoku::<2>::default()[far];
+}
+
+#[test]
+#[should_panic = "position outside of the grid"]
+fn coordinates_outside_of_grid() {
+    let far = Pos::from_row_col::<11>(120, 120).unwrap();
+    let _ = far.row_col::<2>();
+}
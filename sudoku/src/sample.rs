@@ -0,0 +1,278 @@
+use rand::{Rng, RngExt};
+
+use crate::{Pos, Size, Storage, Sudoku, Transform};
+
+/// Draws full grids uniformly at random
+///
+/// [Sudoku::generate_full] follows the random choices of a backtracking
+/// search, which favours the grids reached through fewer choices. Instead:
+///
+/// - Grids of at most [Sampler::EXACT_CELLS] cells are all enumerated once,
+///   and each sample picks one of them: the samples are exactly uniform and
+///   independent.
+/// - Larger grids are the states of a Markov chain, taken every
+///   [Sampler::steps] moves. A move swaps the values of two rows of a band,
+///   or two columns of a stack, on the smallest set of cells keeping the grid
+///   valid, or applies a random [Transform]. Each move is undone by the same
+///   move, drawn with the same probability, so the chain is symmetric and the
+///   uniform distribution is stationary: once mixed, the samples are uniform.
+///
+/// For the chain to converge to the uniform distribution, every full grid
+/// must be reachable from every other. This is checked exhaustively on 4×4
+/// grids, but not proven for larger ones. Successive samples are correlated,
+/// less and less so with more steps.
+#[derive(Debug, Clone)]
+pub struct Sampler<const N: usize> {
+    /// Every full grid, when they are enumerated
+    all: Vec<Vec<u32>>,
+    /// The state of the chain, its values in reading order
+    values: Vec<u32>,
+    steps: usize,
+}
+
+impl<const N: usize> Sampler<N>
+where
+    Size<N>: Storage,
+{
+    /// Grids with at most this many cells are enumerated
+    pub const EXACT_CELLS: usize = 16;
+
+    /// The default number of moves between two samples of the chain
+    ///
+    /// It is an estimate, not a proven mixing time. On 9×9 grids, the number
+    /// of bands and stacks whose squares are split in the same sets of values,
+    /// which no [Transform] changes, is no longer correlated between samples
+    /// after about `N⁴` moves. The default takes 16 times as many.
+    pub const STEPS: usize = 16 * N * N * N * N;
+
+    const R: usize = N * N;
+
+    /// A sampler taking [Sampler::STEPS] moves between samples
+    pub fn new(rng: impl Rng) -> Self {
+        Self::with_steps(Self::STEPS, rng)
+    }
+
+    /// A sampler taking `steps` moves between samples, and ten times as many
+    /// to forget its starting grid
+    pub fn with_steps(steps: usize, mut rng: impl Rng) -> Self {
+        let all = match N * N * N * N <= Self::EXACT_CELLS {
+            true => Sudoku::<N>::default()
+                .brute_force((), 0..)
+                .map(|grid| values(&grid))
+                .collect(),
+            false => Vec::new(),
+        };
+        let mut sampler = Self {
+            all,
+            values: values(&Sudoku::<N>::generate_shuffled(&mut rng)),
+            steps,
+        };
+        if !sampler.is_exact() {
+            for _ in 0..10 * steps {
+                sampler.step(&mut rng);
+            }
+        }
+        sampler
+    }
+
+    /// Whether the samples are exactly uniform and independent
+    pub fn is_exact(&self) -> bool {
+        !self.all.is_empty()
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// The next full grid
+    pub fn sample(&mut self, mut rng: impl Rng) -> Sudoku<N> {
+        if self.is_exact() {
+            let values = &self.all[rng.random_range(0..self.all.len())];
+            return Sudoku::filled(|pos| values[pos.index::<N>()]);
+        }
+        for _ in 0..self.steps {
+            self.step(&mut rng);
+        }
+        Sudoku::filled(|pos| self.values[pos.index::<N>()])
+    }
+
+    fn step(&mut self, mut rng: impl Rng) {
+        match rng.random_range(0..3) {
+            0 => self.swap_lines(&mut rng, false),
+            1 => self.swap_lines(&mut rng, true),
+            _ => {
+                let transform = Transform::<N>::random(&mut rng);
+                let mut moved = vec![0; self.values.len()];
+                for (pos, &value) in Pos::iter::<N>().zip(&self.values) {
+                    moved[transform.map_pos(pos).index::<N>()] = transform.map_value(value);
+                }
+                self.values = moved;
+            }
+        }
+    }
+
+    // Swap two rows of a band, or two columns of a stack, along a cycle of
+    // cells: each value leaving a line must come back to it from the other.
+    // The squares are unchanged, each swap staying within one of them.
+    fn swap_lines(&mut self, rng: &mut impl Rng, columns: bool) {
+        let r = Self::R;
+        let band = rng.random_range(0..N) * N;
+        let first = rng.random_range(0..N);
+        let second = band + (first + rng.random_range(1..N)) % N;
+        let first = band + first;
+        let at = |line: usize, i: usize| match columns {
+            true => i * r + line,
+            false => line * r + i,
+        };
+
+        // where each value is in the first line
+        let mut place = vec![0; r];
+        for i in 0..r {
+            place[self.values[at(first, i)] as usize] = i;
+        }
+        let start = rng.random_range(0..r);
+        let mut i = start;
+        loop {
+            let value = self.values[at(second, i)];
+            self.values.swap(at(first, i), at(second, i));
+            i = place[value as usize];
+            if i == start {
+                break;
+            }
+        }
+    }
+}
+
+// The values of a full grid, in reading order
+fn values<const N: usize>(grid: &Sudoku<N>) -> Vec<u32>
+where
+    Size<N>: Storage,
+{
+    Pos::iter::<N>()
+        .map(|pos| grid[pos].get_value().expect("the grid is full"))
+        .collect()
+}
+
+#[test]
+fn exact_4x4() {
+    use rand::{SeedableRng, rngs::SmallRng};
+    use std::collections::HashMap;
+
+    let mut rng = SmallRng::seed_from_u64(1);
+    let mut sampler = Sampler::<2>::new(&mut rng);
+    assert!(sampler.is_exact());
+    assert_eq!(sampler.all.len(), 288);
+    let mut counts = HashMap::new();
+    for _ in 0..288 * 20 {
+        *counts.entry(values(&sampler.sample(&mut rng))).or_insert(0) += 1;
+    }
+    assert_eq!(counts.len(), 288);
+    assert!(counts.values().all(|&count| (5..=40).contains(&count)));
+}
+
+#[test]
+fn chain_reaches_every_4x4_grid() {
+    use rand::{SeedableRng, rngs::SmallRng};
+    use std::collections::HashSet;
+
+    let mut rng = SmallRng::seed_from_u64(2);
+    let mut chain = Sampler::<2> {
+        all: Vec::new(),
+        values: values(&Sudoku::<2>::pattern()),
+        steps: 1,
+    };
+    let mut seen = HashSet::new();
+    for _ in 0..20_000 {
+        chain.step(&mut rng);
+        assert!(Sudoku::<2>::filled(|pos| chain.values[pos.index::<2>()]).is_accepting());
+        seen.insert(chain.values.clone());
+    }
+    assert_eq!(seen.len(), 288);
+}
+
+#[test]
+fn chain_4x4_uniform() {
+    use rand::{SeedableRng, rngs::SmallRng};
+    use std::collections::HashMap;
+
+    let mut rng = SmallRng::seed_from_u64(4);
+    let mut chain = Sampler::<2> {
+        all: Vec::new(),
+        values: values(&Sudoku::<2>::pattern()),
+        steps: Sampler::<2>::STEPS,
+    };
+    let samples = 288 * 20;
+    let mut counts = HashMap::new();
+    for _ in 0..samples {
+        *counts.entry(values(&chain.sample(&mut rng))).or_insert(0) += 1;
+    }
+    // chi-square with 287 degrees of freedom, 380 is beyond its 0.999 quantile
+    let expected = samples as f64 / 288.0;
+    let unseen = (288 - counts.len()) as f64 * expected;
+    let chi_square: f64 = counts
+        .values()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum::<f64>()
+        + unseen;
+    assert!(chi_square < 380.0, "chi-square {chi_square}");
+}
+
+// The number of bands and stacks whose squares are split in the same three
+// sets of values by their rows, or columns, which no [Transform] changes
+#[cfg(test)]
+fn pure_bands(grid: &Sudoku<3>) -> usize {
+    let mut count = 0;
+    for columns in [false, true] {
+        for band in 0..3 {
+            let splits: Vec<Vec<u32>> = (0..3)
+                .map(|square| {
+                    let mut split: Vec<u32> = (0..3)
+                        .map(|line| {
+                            (0..3).fold(0, |set, i| {
+                                let (row, col) = (band * 3 + line, square * 3 + i);
+                                let (row, col) = if columns { (col, row) } else { (row, col) };
+                                let pos = Pos::from_row_col::<3>(row, col).unwrap();
+                                set | 1 << grid[pos].get_value().unwrap()
+                            })
+                        })
+                        .collect();
+                    split.sort_unstable();
+                    split
+                })
+                .collect();
+            count += splits.iter().all(|split| *split == splits[0]) as usize;
+        }
+    }
+    count
+}
+
+#[test]
+fn chain_9x9() {
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    // Lag-1 autocorrelation of the pure bands of successive samples
+    fn correlation(steps: usize) -> f64 {
+        let mut rng = SmallRng::seed_from_u64(3);
+        let mut sampler = Sampler::<3>::with_steps(steps, &mut rng);
+        assert!(!sampler.is_exact());
+        let counts: Vec<f64> = (0..200)
+            .map(|_| {
+                let grid = sampler.sample(&mut rng);
+                assert!(grid.is_accepting());
+                pure_bands(&grid) as f64
+            })
+            .collect();
+        let mean = counts.iter().sum::<f64>() / counts.len() as f64;
+        let variance: f64 = counts.iter().map(|c| (c - mean).powi(2)).sum();
+        let covariance: f64 = counts
+            .windows(2)
+            .map(|w| (w[0] - mean) * (w[1] - mean))
+            .sum();
+        covariance / variance
+    }
+
+    // a single move rarely changes the class of the grid,
+    // after the default steps, successive samples are unrelated
+    assert!(correlation(1) > 0.5);
+    assert!(correlation(Sampler::<3>::STEPS).abs() < 0.25);
+}
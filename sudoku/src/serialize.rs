@@ -0,0 +1,440 @@
+use std::fmt;
+
+use serde::de::{self, Error as _, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{AnySudoku, Cell, Defer, Pos, Size, Storage, Sudoku};
+
+// Human readable formats, like JSON, number the values from 1 as in
+// [Sudoku::from_numbers], compact ones pack the candidates in bytes
+
+// A cell is its value when known, otherwise the list of its candidates
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum Readable {
+    Value(u32),
+    Candidates(Vec<u32>),
+}
+
+fn readable<const N: usize>(cell: Cell<N>) -> Readable
+where
+    Size<N>: Storage,
+{
+    match cell.get_value() {
+        Some(value) => Readable::Value(value + 1),
+        None => Readable::Candidates(cell.map(|value| value + 1).collect()),
+    }
+}
+
+fn from_numbers<const N: usize, E: de::Error>(numbers: &[u32]) -> Result<Cell<N>, E>
+where
+    Size<N>: Storage,
+{
+    numbers.iter().try_fold(Cell::EMPTY, |cell, &number| {
+        match number.checked_sub(1).filter(|value| *value < Cell::<N>::R) {
+            Some(value) => Ok(cell | Cell::from_value(value)),
+            None => Err(E::custom(format_args!(
+                "invalid value {number}, expecting 1 to {}",
+                Cell::<N>::R
+            ))),
+        }
+    })
+}
+
+fn from_readable<const N: usize, E: de::Error>(readable: Readable) -> Result<Cell<N>, E>
+where
+    Size<N>: Storage,
+{
+    match readable {
+        Readable::Value(number) => from_numbers(&[number]),
+        Readable::Candidates(numbers) => from_numbers(&numbers),
+    }
+}
+
+fn bytes_per_cell<const N: usize>() -> usize {
+    (N * N).div_ceil(8)
+}
+
+fn push_bytes<const N: usize>(bytes: &mut Vec<u8>, cell: Cell<N>)
+where
+    Size<N>: Storage,
+{
+    let start = bytes.len();
+    bytes.resize(start + bytes_per_cell::<N>(), 0);
+    for value in cell {
+        bytes[start + value as usize / 8] |= 1 << (value % 8);
+    }
+}
+
+fn from_bytes<const N: usize>(bytes: &[u8]) -> Cell<N>
+where
+    Size<N>: Storage,
+{
+    (0..Cell::<N>::R)
+        .filter(|value| bytes[*value as usize / 8] >> (value % 8) & 1 == 1)
+        .fold(Cell::EMPTY, |cell, value| cell | Cell::from_value(value))
+}
+
+// Accepts bytes from the formats that have them, and sequences from the others
+struct Bytes(usize);
+
+impl<'de> Visitor<'de> for Bytes {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} bytes", self.0)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Self::Value, E> {
+        match bytes.len() == self.0 {
+            true => Ok(bytes.to_vec()),
+            false => Err(E::invalid_length(bytes.len(), &self)),
+        }
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(self.0);
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+/// Its value when known, otherwise its candidates, numbered from 1
+impl<const N: usize> Serialize for Cell<N>
+where
+    Size<N>: Storage,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            readable(*self).serialize(serializer)
+        } else {
+            let mut bytes = Vec::new();
+            push_bytes(&mut bytes, *self);
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for Cell<N>
+where
+    Size<N>: Storage,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            from_readable(Readable::deserialize(deserializer)?)
+        } else {
+            let bytes = deserializer.deserialize_bytes(Bytes(bytes_per_cell::<N>()))?;
+            Ok(from_bytes(&bytes))
+        }
+    }
+}
+
+// The coordinates of a position, checked before it is built
+#[derive(Deserialize)]
+pub(crate) struct PosFields {
+    stack: u8,
+    col_in_stack: u8,
+    band: u8,
+    row_in_band: u8,
+}
+
+impl TryFrom<PosFields> for Pos {
+    type Error = String;
+
+    fn try_from(fields: PosFields) -> Result<Self, Self::Error> {
+        let PosFields {
+            stack,
+            col_in_stack,
+            band,
+            row_in_band,
+        } = fields;
+        let max = AnySudoku::MAX_SIZE as u8;
+        if [stack, col_in_stack, band, row_in_band]
+            .iter()
+            .any(|c| *c >= max)
+        {
+            return Err(format!("coordinates of a position must be below {max}"));
+        }
+        Ok(Pos {
+            x_1: stack,
+            x_2: col_in_stack,
+            y_1: band,
+            y_2: row_in_band,
+        })
+    }
+}
+
+// The known values, given or not, row by row with `0` for the others,
+// and the candidates of the others, empty for the known ones
+#[derive(Serialize, Deserialize)]
+struct ReadableGrid {
+    values: Vec<Vec<u32>>,
+    #[serde(default)]
+    candidates: Vec<Vec<Vec<u32>>>,
+}
+
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    // The grid whose cells have the given candidates, if they are coherent
+    fn from_cells<E: de::Error>(cells: &[Cell<N>]) -> Result<Self, E> {
+        let values: Vec<Option<u32>> = cells.iter().map(|cell| cell.get_value()).collect();
+        let mut grid = Self::from_values(&values).map_err(E::custom)?;
+        let mut defer = Defer::new();
+        for (pos, cell) in Pos::iter::<N>().zip(cells) {
+            if cell.is_empty() || grid.remove_all(!*cell, pos, &mut defer).is_none() {
+                let (row, col) = pos.row_col::<N>();
+                return Err(E::custom(format_args!(
+                    "no candidate left at row {}, column {}",
+                    row + 1,
+                    col + 1
+                )));
+            }
+        }
+        Ok(grid)
+    }
+}
+
+/// The known values and candidates of the cells, not the moves of the search
+impl<const N: usize> Serialize for Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            let cells: Vec<Pos> = Pos::iter::<N>().collect();
+            let rows = cells.chunks(N * N);
+            ReadableGrid {
+                values: rows
+                    .clone()
+                    .map(|row| {
+                        row.iter()
+                            .map(|pos| self[*pos].get_value().map_or(0, |v| v + 1))
+                            .collect()
+                    })
+                    .collect(),
+                candidates: rows
+                    .map(|row| {
+                        row.iter()
+                            .map(|pos| match readable(self[*pos]) {
+                                Readable::Value(_) => Vec::new(),
+                                Readable::Candidates(numbers) => numbers,
+                            })
+                            .collect()
+                    })
+                    .collect(),
+            }
+            .serialize(serializer)
+        } else {
+            let mut bytes = Vec::with_capacity(N * N * N * N * bytes_per_cell::<N>());
+            for pos in Pos::iter::<N>() {
+                push_bytes(&mut bytes, self[pos]);
+            }
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+}
+
+/// The candidates may be omitted, the grid is then made of its known values
+impl<'de, const N: usize> Deserialize<'de> for Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cells: Vec<Cell<N>> = if deserializer.is_human_readable() {
+            let ReadableGrid { values, candidates } = ReadableGrid::deserialize(deserializer)?;
+            let rows = values.len();
+            if rows != N * N || values.iter().any(|row| row.len() != N * N) {
+                return Err(D::Error::custom(format_args!(
+                    "expecting {} rows of {} values",
+                    N * N,
+                    N * N
+                )));
+            }
+            if !candidates.is_empty() && candidates.len() != rows {
+                return Err(D::Error::invalid_length(
+                    candidates.len(),
+                    &"one row per row of values",
+                ));
+            }
+            values
+                .into_iter()
+                .flatten()
+                .zip(
+                    candidates
+                        .into_iter()
+                        .flatten()
+                        .map(Some)
+                        .chain(std::iter::repeat(None)),
+                )
+                .map(|(value, candidates)| match (value, candidates) {
+                    (0, Some(numbers)) if !numbers.is_empty() => from_numbers(&numbers),
+                    (0, _) => Ok(Cell::FULL),
+                    (value, _) => from_numbers(&[value]),
+                })
+                .collect::<Result<_, _>>()?
+        } else {
+            let size = bytes_per_cell::<N>();
+            let bytes = deserializer.deserialize_bytes(Bytes(N * N * N * N * size))?;
+            bytes.chunks(size).map(from_bytes).collect()
+        };
+        Self::from_cells(&cells)
+    }
+}
+
+/// Serde support for the masks of the given cells
+///
+/// To be used with `#[serde(with = "sudoku::serde_mask")]`. Human readable
+/// formats write a string per row, `x` for given cells and `.` for the others.
+pub mod serde_mask {
+    use super::*;
+
+    type Mask<const N: usize> = [[[[bool; N]; N]; N]; N];
+
+    pub fn serialize<S: Serializer, const N: usize>(
+        mask: &Mask<N>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let shown = Pos::iter::<N>().map(|pos| mask[pos]);
+        if serializer.is_human_readable() {
+            let shown: Vec<char> = shown.map(|shown| if shown { 'x' } else { '.' }).collect();
+            let rows: Vec<String> = shown.chunks(N * N).map(String::from_iter).collect();
+            rows.serialize(serializer)
+        } else {
+            let mut bytes = vec![0u8; (N * N * N * N).div_ceil(8)];
+            for (i, shown) in shown.enumerate() {
+                bytes[i / 8] |= (shown as u8) << (i % 8);
+            }
+            serializer.serialize_bytes(&bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<Mask<N>, D::Error> {
+        let cells = N * N * N * N;
+        let shown: Vec<bool> = if deserializer.is_human_readable() {
+            let rows = Vec::<String>::deserialize(deserializer)?;
+            let shown = rows
+                .iter()
+                .flat_map(|row| row.chars())
+                .map(|c| match c {
+                    'x' => Ok(true),
+                    '.' => Ok(false),
+                    c => Err(D::Error::invalid_value(
+                        de::Unexpected::Char(c),
+                        &"`x` or `.`",
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if rows.len() != N * N || shown.len() != cells {
+                return Err(D::Error::invalid_length(
+                    shown.len(),
+                    &"a cell per cell of the grid",
+                ));
+            }
+            shown
+        } else {
+            let bytes = deserializer.deserialize_bytes(Bytes(cells.div_ceil(8)))?;
+            (0..cells)
+                .map(|i| bytes[i / 8] >> (i % 8) & 1 == 1)
+                .collect()
+        };
+        let mut mask = crate::mask_empty();
+        for (pos, shown) in Pos::iter::<N>().zip(shown) {
+            mask[pos] = shown;
+        }
+        Ok(mask)
+    }
+}
+
+// The moves leading to the cells differ between grids loaded differently
+#[cfg(test)]
+fn same_cells<const N: usize>(a: &Sudoku<N>, b: &Sudoku<N>) -> bool
+where
+    Size<N>: Storage,
+{
+    Pos::iter::<N>().all(|pos| a[pos] == b[pos])
+}
+
+#[test]
+fn json_and_binary() {
+    let grid = Sudoku::<2>::from_numbers("1 0 0 0\n0 0 0 0\n0 0 0 0\n0 0 0 0").unwrap();
+    let json = serde_json::to_string(&grid).unwrap();
+    assert!(
+        json.starts_with(r#"{"values":[[1,0,0,0],[0,0,0,0]"#),
+        "{json}"
+    );
+    assert!(
+        json.contains(r#""candidates":[[[],[2,3,4],[2,3,4],[2,3,4]],"#),
+        "{json}"
+    );
+    assert!(same_cells(&serde_json::from_str(&json).unwrap(), &grid));
+
+    // the candidates are optional
+    let values = r#"{"values":[[1,0,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]]}"#;
+    assert!(same_cells(&serde_json::from_str(values).unwrap(), &grid));
+    let clash = r#"{"values":[[1,1,0,0],[0,0,0,0],[0,0,0,0],[0,0,0,0]]}"#;
+    assert!(serde_json::from_str::<Sudoku<2>>(clash).is_err());
+
+    let binary = bincode::serialize(&grid).unwrap();
+    assert_eq!(binary.len(), 8 + 16);
+    assert!(same_cells(&bincode::deserialize(&binary).unwrap(), &grid));
+
+    let cell = Cell::<3>::from_value(4) | Cell::from_value(8);
+    assert_eq!(serde_json::to_string(&cell).unwrap(), "[5,9]");
+    assert_eq!(
+        serde_json::to_string(&Cell::<3>::from_value(4)).unwrap(),
+        "5"
+    );
+    assert_eq!(serde_json::from_str::<Cell<3>>("[5,9]").unwrap(), cell);
+    assert!(serde_json::from_str::<Cell<3>>("10").is_err());
+    let binary = bincode::serialize(&cell).unwrap();
+    assert_eq!(bincode::deserialize::<Cell<3>>(&binary).unwrap(), cell);
+
+    let pos = Pos::from_row_col::<3>(4, 7).unwrap();
+    let json = serde_json::to_string(&pos).unwrap();
+    assert_eq!(
+        json,
+        r#"{"stack":2,"col_in_stack":1,"band":1,"row_in_band":1}"#
+    );
+    assert_eq!(serde_json::from_str::<Pos>(&json).unwrap(), pos);
+    let far = r#"{"stack":2,"col_in_stack":11,"band":1,"row_in_band":1}"#;
+    assert!(serde_json::from_str::<Pos>(far).is_err());
+    let outside = r#"{"stack":0,"col_in_stack":0,"band":10,"row_in_band":0}"#;
+    let outside = serde_json::from_str::<Pos>(outside).unwrap();
+    assert!(!outside.is_valid::<3>());
+    let grid = Sudoku::<3>::default();
+    assert!(std::panic::catch_unwind(|| grid[outside]).is_err());
+    let binary = bincode::serialize(&pos).unwrap();
+    assert_eq!(bincode::deserialize::<Pos>(&binary).unwrap(), pos);
+}
+
+#[test]
+fn masks() {
+    #[derive(Serialize, Deserialize)]
+    struct Puzzle {
+        grid: Sudoku<2>,
+        #[serde(with = "serde_mask")]
+        mask: [[[[bool; 2]; 2]; 2]; 2],
+    }
+    let grid = Sudoku::<2>::from_numbers("1 0 0 0\n0 0 0 0\n0 0 0 0\n0 0 0 0").unwrap();
+    let mut mask = crate::mask_empty();
+    mask[Pos::from_row_col::<2>(1, 2).unwrap()] = true;
+    let puzzle = Puzzle { grid, mask };
+
+    let json = serde_json::to_string(&puzzle).unwrap();
+    assert!(
+        json.ends_with(r#""mask":["....","..x.","....","...."]}"#),
+        "{json}"
+    );
+    let read: Puzzle = serde_json::from_str(&json).unwrap();
+    assert!(same_cells(&read.grid, &puzzle.grid));
+    assert_eq!(read.mask, puzzle.mask);
+    let binary = bincode::serialize(&puzzle).unwrap();
+    let read: Puzzle = bincode::deserialize(&binary).unwrap();
+    assert_eq!(read.mask, puzzle.mask);
+}
@@ -0,0 +1,239 @@
+use crate::{
+    Cell, Choose, Defer, Pos, Size, Storage, Sudoku,
+    nogood::{Analysis, Literal, Nogoods, merge_refutation},
+};
+
+/// Iterator over the solutions of a grid, see [Sudoku::brute_force]
+///
+/// The search is resumed on each call to `next`.
+/// The grid is borrowed for the whole search and restored
+/// to its initial state when the iterator is dropped.
+pub struct Solutions<'a, const N: usize, C, T>
+where
+    Size<N>: Storage,
+{
+    grid: &'a mut Sudoku<N>,
+    chooser: C,
+    ttl: T,
+    started: bool,
+    done: bool,
+    /// Moves pushed by the last yielded solution
+    yielded: Option<usize>,
+    /// The cell of the current bifurcation
+    pos: Pos,
+    /// Values not tried yet
+    cell: Cell<N>,
+    /// Values of the cell when the bifurcation started
+    initial: Cell<N>,
+    /// Literals refuting the values tried so far, if known
+    refuted: Option<Vec<Literal>>,
+    stack: Vec<Level<N>>,
+    persist: Defer<N>,
+    nogoods: Nogoods<N>,
+    analysis: Option<Analysis<N>>,
+}
+
+// One bifurcation of the backtracking search
+struct Level<const N: usize>
+where
+    Size<N>: Storage,
+{
+    /// Number of moves pushed by the decision
+    moved: usize,
+    /// Values not tried yet
+    cell: Cell<N>,
+    /// Values of the cell when the bifurcation started
+    initial: Cell<N>,
+    /// Literals refuting the values already tried, if known
+    refuted: Option<Vec<Literal>>,
+    /// Value chosen in the cell
+    decision: (Pos, u32),
+}
+
+impl<'a, const N: usize, C, T> Solutions<'a, N, C, T>
+where
+    Size<N>: Storage,
+{
+    pub(crate) fn new(grid: &'a mut Sudoku<N>, chooser: C, ttl: T, capacity: usize) -> Self {
+        Self {
+            grid,
+            chooser,
+            ttl,
+            started: false,
+            done: false,
+            yielded: None,
+            pos: Pos::default(),
+            cell: Cell::EMPTY,
+            initial: Cell::EMPTY,
+            refuted: Some(Vec::new()),
+            stack: Vec::new(),
+            persist: Defer::new(),
+            nogoods: Nogoods::new(capacity),
+            analysis: None,
+        }
+    }
+
+    // Undo every move of the search, the grid is back to its initial state
+    fn unwind(&mut self) {
+        if let Some(moved) = self.yielded.take() {
+            self.grid.pop_n_moves(moved);
+        }
+        for level in self.stack.drain(..).rev() {
+            self.grid.pop_n_moves(level.moved);
+        }
+        self.done = true;
+    }
+}
+
+impl<const N: usize, C, T> Iterator for Solutions<'_, N, C, T>
+where
+    Size<N>: Storage,
+    C: Choose<N>,
+    T: Iterator<Item = usize>,
+{
+    type Item = Sudoku<N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if !self.started {
+            self.started = true;
+            let min = self.grid.best();
+            if min == 1 {
+                self.done = true;
+                return Some(self.grid.clone());
+            }
+            // let pos_iter = chooser.pos_iter();
+            self.pos = self.grid.min_bifurc(min);
+            self.cell = self.grid[self.pos];
+            self.initial = self.cell;
+            if self.nogoods.is_enabled() {
+                self.analysis = Some(Analysis::new(self.grid));
+            }
+        }
+        if let Some(moved) = self.yielded.take() {
+            self.grid.pop_n_moves(moved);
+            // a solution exists, nothing on the path is refuted
+            self.refuted = None;
+            for level in &mut self.stack {
+                level.refuted = None;
+            }
+        }
+
+        let grid = &mut *self.grid;
+        while self.ttl.next().is_some() {
+            if let Some(value) = self.chooser.choose_pop_value_in_cell(&mut self.cell) {
+                let pos = self.pos;
+                let mut moved = 0;
+                let propagated = grid
+                    .remove_all_traced(!Cell::from_value(value), pos, &mut moved, &mut self.persist)
+                    .and_then(|()| self.nogoods.propagate(grid, &mut moved, &mut self.persist));
+                match propagated {
+                    Err(conflict) => {
+                        // the moves leading to the conflict explain it
+                        if let Some(analysis) = &mut self.analysis {
+                            let start = grid.move_count() - moved;
+                            let refutation = analysis.explain(
+                                grid,
+                                &conflict,
+                                start,
+                                (pos, value),
+                                &self.nogoods,
+                            );
+                            merge_refutation(&mut self.refuted, refutation.as_deref());
+                            if let Some(mut nogood) = refutation {
+                                nogood.push(Literal::Has(pos, value));
+                                nogood.sort_unstable();
+                                self.nogoods.learn(nogood);
+                            }
+                        }
+                        grid.pop_n_moves(moved);
+                    }
+                    Ok(()) => match grid.best() {
+                        1 => {
+                            self.yielded = Some(moved);
+                            return Some(grid.clone());
+                        }
+                        min => {
+                            self.stack.push(Level {
+                                moved,
+                                cell: self.cell,
+                                initial: self.initial,
+                                refuted: self.refuted.take(),
+                                decision: (pos, value),
+                            });
+                            self.pos = grid.min_bifurc(min);
+                            self.cell = grid[self.pos];
+                            self.initial = self.cell;
+                            self.refuted = Some(Vec::new());
+                        }
+                    },
+                }
+            } else {
+                // every value of the cell failed, maybe because of literals
+                // holding since long before, the levels in between can't
+                // change them and are skipped
+                let mut refutation = None;
+                if let (Some(analysis), Some(refuted)) = (&mut self.analysis, self.refuted.take()) {
+                    let reason = analysis.restrict(grid, self.pos, self.initial, refuted);
+                    let latest = reason.iter().map(|literal| analysis.when(*literal)).max();
+                    let Some(latest) = latest else {
+                        // it fails whatever the search decided, no solution is left
+                        break;
+                    };
+                    let starts = starts(&self.stack, analysis.root());
+                    let back = starts.partition_point(|start| *start <= latest) - 1;
+                    let level = &self.stack[back];
+                    refutation = analysis.cut(
+                        reason.iter().copied(),
+                        starts[back],
+                        level.decision,
+                        &self.nogoods,
+                    );
+                    self.nogoods.learn(reason);
+                    for level in self.stack.drain(back + 1..).rev() {
+                        grid.pop_n_moves(level.moved);
+                    }
+                }
+                let Some(level) = self.stack.pop() else {
+                    self.done = true;
+                    return None;
+                };
+                grid.pop_n_moves(level.moved);
+                self.cell = level.cell;
+                self.initial = level.initial;
+                self.refuted = level.refuted;
+                (self.pos, _) = level.decision;
+                merge_refutation(&mut self.refuted, refutation.as_deref());
+            }
+        }
+        self.unwind();
+        None
+    }
+}
+
+// The number of moves on the grid before each level
+fn starts<const N: usize>(stack: &[Level<N>], root: usize) -> Vec<usize>
+where
+    Size<N>: Storage,
+{
+    let mut start = root;
+    stack
+        .iter()
+        .map(|level| {
+            let before = start;
+            start += level.moved;
+            before
+        })
+        .collect()
+}
+
+impl<const N: usize, C, T> Drop for Solutions<'_, N, C, T>
+where
+    Size<N>: Storage,
+{
+    fn drop(&mut self) {
+        self.unwind();
+    }
+}
@@ -0,0 +1,92 @@
+use std::io::IsTerminal;
+
+/// How [Sudoku::print_styled](crate::Sudoku::print_styled) draws a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    /// Colour the values with ANSI escape codes, according to their [Shade]
+    pub colors: bool,
+    /// Draw the borders with ASCII characters instead of box-drawing ones
+    pub ascii: bool,
+}
+
+impl Style {
+    /// Box-drawing borders and no colour
+    pub fn plain() -> Self {
+        Self::default()
+    }
+
+    /// Colours when the standard output is a terminal, and `NO_COLOR` isn't set
+    pub fn detect() -> Self {
+        Self {
+            colors: std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+            ascii: false,
+        }
+    }
+}
+
+/// Where a value shown in a grid comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shade {
+    /// A given of the puzzle
+    Given,
+    /// Found by the solver
+    Solved,
+    /// Entered by a player
+    Entry,
+    /// Clashing with another value of its row, column or square
+    Conflict,
+}
+
+impl Shade {
+    pub(crate) fn ansi(self) -> &'static str {
+        match self {
+            Shade::Given => "\x1b[1m",
+            Shade::Solved => "\x1b[34m",
+            Shade::Entry => "\x1b[32m",
+            Shade::Conflict => "\x1b[1;41;97m",
+        }
+    }
+}
+
+pub(crate) const RESET: &str = "\x1b[0m";
+
+// The characters drawing a grid
+pub(crate) struct Borders {
+    pub bold: char,
+    pub thin: char,
+    pub bold_side: char,
+    pub thin_side: char,
+    /// Left, right, thin crossing and bold crossing
+    /// of the top, bold, thin and bottom separators
+    pub separators: [[char; 4]; 4],
+}
+
+impl Borders {
+    pub fn of(style: Style) -> &'static Self {
+        match style.ascii {
+            true => &ASCII,
+            false => &BOX,
+        }
+    }
+}
+
+const BOX: Borders = Borders {
+    bold: '━',
+    thin: '─',
+    bold_side: '┃',
+    thin_side: '│',
+    separators: [
+        ['┏', '┓', '┯', '┳'],
+        ['┣', '┫', '┿', '╋'],
+        ['┠', '┨', '┼', '╂'],
+        ['┗', '┛', '┷', '┻'],
+    ],
+};
+
+const ASCII: Borders = Borders {
+    bold: '=',
+    thin: '-',
+    bold_side: '|',
+    thin_side: ':',
+    separators: [['+'; 4]; 4],
+};
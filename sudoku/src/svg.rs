@@ -0,0 +1,404 @@
+use std::io::Write;
+
+use crate::{Alphabet, Pos, Size, Storage, Sudoku};
+
+/// How [Sudoku::write_svg] draws a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SvgOptions {
+    /// Side of a cell, in pixels
+    pub cell_size: u32,
+    /// Draw the known values outside of the mask, in another colour than the givens
+    pub filled: bool,
+    /// Draw the candidates of the undetermined cells as pencil marks
+    pub candidates: bool,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 40,
+            filled: true,
+            candidates: false,
+        }
+    }
+}
+
+/// Variant constraints drawn over a grid by [Sudoku::write_svg_marked]
+///
+/// They are only drawn, the solver doesn't know about them.
+/// Cells are given by row and column, both in `0..N*N`, others are skipped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Markings {
+    /// The diagonal from the top left corner
+    pub diagonal: bool,
+    /// The diagonal from the top right corner
+    pub anti_diagonal: bool,
+    pub cages: Vec<Cage>,
+    pub dots: Vec<Dot>,
+}
+
+/// Cells outlined together, with their sum in the corner if any
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cage {
+    pub cells: Vec<(usize, usize)>,
+    pub sum: Option<u32>,
+}
+
+/// A dot on the edge between two adjacent cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Dot {
+    pub cells: [(usize, usize); 2],
+    /// Filled in black, otherwise white with a black outline
+    pub black: bool,
+}
+
+// What is drawn in a cell
+enum Drawn {
+    Given(u32),
+    Filled(u32),
+    Candidates(Vec<u32>),
+}
+
+const STYLE: &str = "text{font-family:sans-serif;text-anchor:middle;dominant-baseline:central}\
+.given{fill:#000;font-weight:bold}.filled{fill:#2856b8}.mark{fill:#666}\
+.thin{stroke:#888;stroke-width:1}.bold{stroke:#000;stroke-width:3}\
+.diagonal{stroke:#bbb;stroke-width:2}.cage{stroke:#444;stroke-width:1;stroke-dasharray:4 3}\
+.sum{fill:#444;text-anchor:start;dominant-baseline:hanging}.dot{stroke:#000;stroke-width:1}";
+
+// Values beyond the symbols are written as numbers starting at 1
+fn label(value: u32, alphabet: &Alphabet, covered: bool) -> String {
+    match alphabet.symbol(value).filter(|_| covered) {
+        Some('<') => "&lt;".to_string(),
+        Some('>') => "&gt;".to_string(),
+        Some('&') => "&amp;".to_string(),
+        Some(symbol) => symbol.to_string(),
+        None => (value + 1).to_string(),
+    }
+}
+
+// The font size fitting `text` in a box of side `side`
+fn font_size(side: f64, text: &str) -> f64 {
+    let chars = text.chars().count().max(1) as f64;
+    side * 0.6 / (1.0 + (chars - 1.0) * 0.5)
+}
+
+// The dashed outline of a cage, inset in its cells
+//
+// Each side of a cell facing out of the cage is drawn, and each end of it
+// reaches the next cell along the side when that cell is in the cage too,
+// or goes past it to meet the turning outline when the corner is hollow.
+fn write_cage(
+    mut writer: impl Write,
+    nn: usize,
+    cage: &Cage,
+    cell: f64,
+    margin: f64,
+) -> Result<(), std::io::Error> {
+    let inside = |row: isize, col: isize| {
+        row >= 0 && col >= 0 && cage.cells.contains(&(row as usize, col as usize))
+    };
+    let inset = cell * 0.1;
+    let cells = cage
+        .cells
+        .iter()
+        .filter(|(row, col)| *row < nn && *col < nn);
+    for &(row, col) in cells.clone() {
+        let (r, c) = (row as isize, col as isize);
+        let (x, y) = (margin + col as f64 * cell, margin + row as f64 * cell);
+        // outward direction of the side, and the direction along it
+        for (out, along) in [
+            ((-1, 0), (0, 1)),
+            ((1, 0), (0, 1)),
+            ((0, -1), (1, 0)),
+            ((0, 1), (1, 0)),
+        ] {
+            if inside(r + out.0, c + out.1) {
+                continue;
+            }
+            let end = |sign: isize| {
+                let next = (r + sign * along.0, c + sign * along.1);
+                match (
+                    inside(next.0, next.1),
+                    inside(next.0 + out.0, next.1 + out.1),
+                ) {
+                    (false, _) => inset,
+                    (true, false) => 0.0,
+                    (true, true) => -inset,
+                }
+            };
+            let (start, stop) = (end(-1), cell - end(1));
+            let at = match out.0 + out.1 {
+                -1 => inset,
+                _ => cell - inset,
+            };
+            let ((x1, y1), (x2, y2)) = match along {
+                (0, 1) => ((x + start, y + at), (x + stop, y + at)),
+                _ => ((x + at, y + start), (x + at, y + stop)),
+            };
+            writeln!(
+                writer,
+                r#"<line class="cage" x1="{x1:.1}" y1="{y1:.1}" x2="{x2:.1}" y2="{y2:.1}"/>"#
+            )?;
+        }
+    }
+    if let (Some(sum), Some(&(row, col))) = (cage.sum, cells.min()) {
+        writeln!(
+            writer,
+            r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="#fff"/>"##,
+            margin + col as f64 * cell + inset / 2.0,
+            margin + row as f64 * cell + inset / 2.0,
+            cell * 0.3,
+            cell * 0.25,
+        )?;
+        writeln!(
+            writer,
+            r#"<text class="sum" x="{:.1}" y="{:.1}" font-size="{:.1}">{sum}</text>"#,
+            margin + col as f64 * cell + inset / 2.0,
+            margin + row as f64 * cell + inset / 2.0,
+            cell * 0.22,
+        )?;
+    }
+    Ok(())
+}
+
+fn write_svg(
+    mut writer: impl Write,
+    n: usize,
+    cells: &[Drawn],
+    alphabet: &Alphabet,
+    options: &SvgOptions,
+    markings: &Markings,
+) -> Result<(), std::io::Error> {
+    let nn = n * n;
+    let cell = options.cell_size as f64;
+    let margin = 2.0;
+    let side = nn as f64 * cell + 2.0 * margin;
+    let covered = alphabet.covers(n);
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{side}" height="{side}" viewBox="0 0 {side} {side}">"#
+    )?;
+    writeln!(writer, "<style>{STYLE}</style>")?;
+    writeln!(
+        writer,
+        r##"<rect width="{side}" height="{side}" fill="#fff"/>"##
+    )?;
+
+    // under the values, so that they stay readable
+    let end = side - margin;
+    if markings.diagonal {
+        writeln!(
+            writer,
+            r#"<line class="diagonal" x1="{margin}" y1="{margin}" x2="{end}" y2="{end}"/>"#
+        )?;
+    }
+    if markings.anti_diagonal {
+        writeln!(
+            writer,
+            r#"<line class="diagonal" x1="{end}" y1="{margin}" x2="{margin}" y2="{end}"/>"#
+        )?;
+    }
+    for cage in &markings.cages {
+        write_cage(&mut writer, nn, cage, cell, margin)?;
+    }
+
+    for (i, drawn) in cells.iter().enumerate() {
+        let (x, y) = (
+            margin + (i % nn) as f64 * cell,
+            margin + (i / nn) as f64 * cell,
+        );
+        match drawn {
+            Drawn::Given(value) | Drawn::Filled(value) => {
+                let class = match drawn {
+                    Drawn::Given(_) => "given",
+                    _ => "filled",
+                };
+                let text = label(*value, alphabet, covered);
+                writeln!(
+                    writer,
+                    r#"<text class="{class}" x="{}" y="{}" font-size="{:.1}">{text}</text>"#,
+                    x + cell / 2.0,
+                    y + cell / 2.0,
+                    font_size(cell, &text)
+                )?;
+            }
+            // A mini-grid of `n` by `n`, each value at its own place
+            Drawn::Candidates(values) => {
+                let mark = cell / n as f64;
+                for value in values {
+                    let text = label(*value, alphabet, covered);
+                    writeln!(
+                        writer,
+                        r#"<text class="mark" x="{:.1}" y="{:.1}" font-size="{:.1}">{text}</text>"#,
+                        x + (*value as usize % n) as f64 * mark + mark / 2.0,
+                        y + (*value as usize / n) as f64 * mark + mark / 2.0,
+                        font_size(mark, &text) * 1.2
+                    )?;
+                }
+            }
+        }
+    }
+
+    // thin lines first, so that the bold ones are drawn over them
+    for bold in [false, true] {
+        let class = if bold { "bold" } else { "thin" };
+        for i in 0..=nn {
+            if (i % n == 0) != bold {
+                continue;
+            }
+            let at = margin + i as f64 * cell;
+            let end = side - margin;
+            writeln!(
+                writer,
+                r#"<line class="{class}" x1="{at}" y1="{margin}" x2="{at}" y2="{end}"/>"#
+            )?;
+            writeln!(
+                writer,
+                r#"<line class="{class}" x1="{margin}" y1="{at}" x2="{end}" y2="{at}"/>"#
+            )?;
+        }
+    }
+
+    // over the lines, centred on the edge between the two cells
+    let centre = |(row, col): (usize, usize)| {
+        (
+            margin + (col as f64 + 0.5) * cell,
+            margin + (row as f64 + 0.5) * cell,
+        )
+    };
+    for dot in &markings.dots {
+        if dot.cells.iter().any(|(row, col)| *row >= nn || *col >= nn) {
+            continue;
+        }
+        let ((x1, y1), (x2, y2)) = (centre(dot.cells[0]), centre(dot.cells[1]));
+        let fill = if dot.black { "#000" } else { "#fff" };
+        writeln!(
+            writer,
+            r#"<circle class="dot" cx="{:.1}" cy="{:.1}" r="{:.1}" fill="{fill}"/>"#,
+            (x1 + x2) / 2.0,
+            (y1 + y2) / 2.0,
+            cell * 0.12
+        )?;
+    }
+    writeln!(writer, "</svg>")
+}
+
+impl<const N: usize> Sudoku<N>
+where
+    Size<N>: Storage,
+{
+    /// Draw the grid as an SVG image, with the givens of the mask in bold
+    pub fn write_svg(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
+        options: &SvgOptions,
+    ) -> Result<(), std::io::Error> {
+        self.write_svg_marked(writer, mask, alphabet, options, &Markings::default())
+    }
+
+    /// Same as [Sudoku::write_svg], with the markings of a variant
+    pub fn write_svg_marked(
+        &self,
+        writer: impl Write,
+        mask: [[[[bool; N]; N]; N]; N],
+        alphabet: &Alphabet,
+        options: &SvgOptions,
+        markings: &Markings,
+    ) -> Result<(), std::io::Error> {
+        let cells: Vec<Drawn> = Pos::iter::<N>()
+            .map(|pos| match self[pos].get_value() {
+                Some(value) if mask[pos] => Drawn::Given(value),
+                Some(value) if options.filled => Drawn::Filled(value),
+                Some(_) => Drawn::Candidates(Vec::new()),
+                None if options.candidates => Drawn::Candidates(self[pos].collect()),
+                None => Drawn::Candidates(Vec::new()),
+            })
+            .collect();
+        write_svg(writer, N, &cells, alphabet, options, markings)
+    }
+}
+
+#[test]
+fn svg_drawing() {
+    let grid = Sudoku::<2>::from_numbers("1 0 0 0\n0 0 3 0\n0 0 0 0\n0 0 0 0").unwrap();
+    let mut mask = crate::mask_empty();
+    mask[Pos::from_row_col::<2>(0, 0).unwrap()] = true;
+    let options = SvgOptions {
+        candidates: true,
+        ..SvgOptions::default()
+    };
+    let mut svg = Vec::new();
+    grid.write_svg(&mut svg, mask, &Alphabet::standard(), &options)
+        .unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert_eq!(svg.matches(r#"class="given""#).count(), 1);
+    assert!(svg.matches(r#"class="filled""#).count() >= 1);
+    assert!(svg.matches(r#"class="mark""#).count() > 1);
+    // 5 lines each way, 3 of them bold
+    assert_eq!(svg.matches(r#"<line class="bold""#).count(), 6);
+    assert_eq!(svg.matches(r#"<line class="thin""#).count(), 4);
+    assert!(svg.contains(r#"width="164""#));
+
+    let plain = SvgOptions {
+        filled: false,
+        ..SvgOptions::default()
+    };
+    let mut svg = Vec::new();
+    grid.write_svg(&mut svg, mask, &Alphabet::standard(), &plain)
+        .unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+    assert_eq!(svg.matches("<text").count(), 1);
+}
+
+#[test]
+fn svg_markings() {
+    let grid = Sudoku::<2>::default();
+    let markings = Markings {
+        diagonal: true,
+        anti_diagonal: true,
+        cages: vec![Cage {
+            cells: vec![(0, 0), (0, 1), (1, 0)],
+            sum: Some(6),
+        }],
+        dots: vec![
+            Dot {
+                cells: [(2, 2), (2, 3)],
+                black: true,
+            },
+            // beyond the grid, skipped
+            Dot {
+                cells: [(3, 3), (3, 4)],
+                black: false,
+            },
+        ],
+    };
+    let mut svg = Vec::new();
+    grid.write_svg_marked(
+        &mut svg,
+        crate::mask_empty(),
+        &Alphabet::standard(),
+        &SvgOptions::default(),
+        &markings,
+    )
+    .unwrap();
+    let svg = String::from_utf8(svg).unwrap();
+    assert_eq!(svg.matches(r#"class="diagonal""#).count(), 2);
+    // 2 sides of the corner cell face out, 3 of each other one
+    assert_eq!(svg.matches(r#"class="cage""#).count(), 8);
+    // the outline turns at the hollow corner, inset in both cells
+    assert!(
+        svg.contains(r#"x1="38.0" y1="38.0" x2="78.0" y2="38.0""#),
+        "{svg}"
+    );
+    assert!(
+        svg.contains(r#"x1="38.0" y1="38.0" x2="38.0" y2="78.0""#),
+        "{svg}"
+    );
+    assert!(svg.contains(r#"class="sum""#));
+    assert_eq!(svg.matches("<circle").count(), 1);
+    assert!(svg.contains(r##"cx="122.0" cy="102.0" r="4.8" fill="#000""##));
+}
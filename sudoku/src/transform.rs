@@ -0,0 +1,249 @@
+use rand::seq::SliceRandom;
+use rand::{Rng, RngExt};
+
+use crate::{Cell, Defer, Pos, Size, Storage, Sudoku};
+
+/// A symmetry of the sudoku grids
+///
+/// The grid is first transposed, if asked, then its bands, the rows within
+/// each band, its stacks and the columns within each stack are permuted,
+/// and its values relabelled. Valid grids are mapped to valid grids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Transform<const N: usize> {
+    pub(crate) transpose: bool,
+    /// Where each band goes
+    pub(crate) bands: [u8; N],
+    /// Where each row of each band goes, within its new band
+    pub(crate) rows: [[u8; N]; N],
+    /// Where each stack goes
+    pub(crate) stacks: [u8; N],
+    /// Where each column of each stack goes, within its new stack
+    pub(crate) columns: [[u8; N]; N],
+    /// The new label of each value
+    pub(crate) values: Vec<u32>,
+}
+
+// `[0, 1, .., N-1]`
+pub(crate) fn identity<const N: usize>() -> [u8; N] {
+    std::array::from_fn(|i| i as u8)
+}
+
+impl<const N: usize> Transform<N> {
+    /// The transform leaving grids unchanged
+    pub fn identity() -> Self {
+        Self {
+            transpose: false,
+            bands: identity(),
+            rows: [identity(); N],
+            stacks: identity(),
+            columns: [identity(); N],
+            values: (0..(N * N) as u32).collect(),
+        }
+    }
+
+    /// Where the cell at `pos` goes
+    pub fn map_pos(&self, pos: Pos) -> Pos {
+        let Pos { y_1, y_2, x_1, x_2 } = match self.transpose {
+            true => Pos {
+                y_1: pos.x_1,
+                y_2: pos.x_2,
+                x_1: pos.y_1,
+                x_2: pos.y_2,
+            },
+            false => pos,
+        };
+        Pos {
+            y_1: self.bands[y_1 as usize],
+            y_2: self.rows[y_1 as usize][y_2 as usize],
+            x_1: self.stacks[x_1 as usize],
+            x_2: self.columns[x_1 as usize][x_2 as usize],
+        }
+    }
+
+    /// The new label of `value`
+    pub fn map_value(&self, value: u32) -> u32 {
+        self.values[value as usize]
+    }
+
+    pub fn is_transposed(&self) -> bool {
+        self.transpose
+    }
+
+    /// A transform picked uniformly among all of them
+    pub fn random(mut rng: impl Rng) -> Self {
+        let mut transform = Self::identity();
+        transform.transpose = rng.random();
+        transform.bands.shuffle(&mut rng);
+        transform.stacks.shuffle(&mut rng);
+        for rows in &mut transform.rows {
+            rows.shuffle(&mut rng);
+        }
+        for columns in &mut transform.columns {
+            columns.shuffle(&mut rng);
+        }
+        transform.values.shuffle(&mut rng);
+        transform
+    }
+
+    // The same permutations, acting on the other axis
+    fn transposed(&self) -> Self {
+        Self {
+            transpose: self.transpose,
+            bands: self.stacks,
+            rows: self.columns,
+            stacks: self.bands,
+            columns: self.rows,
+            values: self.values.clone(),
+        }
+    }
+
+    /// The transform applying `self`, then `other`
+    pub fn compose(&self, other: &Self) -> Self {
+        // moving the transposition of `other` before the permutations of `self`
+        // swaps the axes they act on
+        let first = match other.transpose {
+            true => self.transposed(),
+            false => self.clone(),
+        };
+        let permute =
+            |outer: [u8; N], inner: [[u8; N]; N], others: [u8; N], other_inner: [[u8; N]; N]| {
+                (
+                    outer.map(|i| others[i as usize]),
+                    std::array::from_fn(|i| {
+                        inner[i].map(|j| other_inner[outer[i] as usize][j as usize])
+                    }),
+                )
+            };
+        let (bands, rows) = permute(first.bands, first.rows, other.bands, other.rows);
+        let (stacks, columns) = permute(first.stacks, first.columns, other.stacks, other.columns);
+        Self {
+            transpose: self.transpose != other.transpose,
+            bands,
+            rows,
+            stacks,
+            columns,
+            values: self
+                .values
+                .iter()
+                .map(|&value| other.map_value(value))
+                .collect(),
+        }
+    }
+
+    /// The transform undoing `self`
+    pub fn inverse(&self) -> Self {
+        let invert = |outer: [u8; N], inner: [[u8; N]; N]| {
+            let mut inverse = (identity::<N>(), [identity::<N>(); N]);
+            for i in 0..N {
+                inverse.0[outer[i] as usize] = i as u8;
+                for (j, &to) in inner[i].iter().enumerate() {
+                    inverse.1[outer[i] as usize][to as usize] = j as u8;
+                }
+            }
+            inverse
+        };
+        let (bands, rows) = invert(self.bands, self.rows);
+        let (stacks, columns) = invert(self.stacks, self.columns);
+        let mut values = vec![0; self.values.len()];
+        for (value, &label) in self.values.iter().enumerate() {
+            values[label as usize] = value as u32;
+        }
+        let inverse = Self {
+            transpose: self.transpose,
+            bands,
+            rows,
+            stacks,
+            columns,
+            values,
+        };
+        // the permutations are undone before the transposition
+        match self.transpose {
+            true => inverse.transposed(),
+            false => inverse,
+        }
+    }
+}
+
+impl<const N: usize> Transform<N>
+where
+    Size<N>: Storage,
+{
+    /// The candidates of `cell`, relabelled
+    pub fn map_cell(&self, cell: Cell<N>) -> Cell<N> {
+        cell.fold(Cell::EMPTY, |mapped, value| {
+            mapped | Cell::from_value(self.map_value(value))
+        })
+    }
+
+    /// The grid with every cell moved and relabelled
+    pub fn apply(&self, grid: &Sudoku<N>) -> Sudoku<N> {
+        let mut cells = [[[[Cell::EMPTY; N]; N]; N]; N];
+        for pos in Pos::iter::<N>() {
+            cells[self.map_pos(pos)] = self.map_cell(grid[pos]);
+        }
+        let mut defer = Defer::new();
+        let mut mapped = Sudoku::default();
+        for pos in Pos::iter::<N>() {
+            mapped
+                .remove_all(!cells[pos], pos, &mut defer)
+                .expect("a symmetry keeps the grid coherent");
+        }
+        mapped
+    }
+
+    /// The mask with every cell moved along with the grid
+    pub fn apply_mask(&self, mask: [[[[bool; N]; N]; N]; N]) -> [[[[bool; N]; N]; N]; N] {
+        let mut mapped = [[[[false; N]; N]; N]; N];
+        for pos in Pos::iter::<N>() {
+            mapped[self.map_pos(pos)] = mask[pos];
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+fn same_cells<const N: usize>(a: &Sudoku<N>, b: &Sudoku<N>) -> bool
+where
+    Size<N>: Storage,
+{
+    Pos::iter::<N>().all(|pos| a[pos] == b[pos])
+}
+
+#[test]
+fn compose_and_inverse() {
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    let mut rng = SmallRng::seed_from_u64(5);
+    let grid = Sudoku::<3>::from_numbers(
+        "5 3 0 0 7 0 0 0 0
+         6 0 0 1 9 5 0 0 0
+         0 9 8 0 0 0 0 6 0
+         8 0 0 0 6 0 0 0 3
+         4 0 0 8 0 3 0 0 1
+         7 0 0 0 2 0 0 0 6
+         0 6 0 0 0 0 2 8 0
+         0 0 0 4 1 9 0 0 5
+         0 0 0 0 8 0 0 7 9",
+    )
+    .unwrap();
+    for _ in 0..20 {
+        let first = Transform::random(&mut rng);
+        let second = Transform::random(&mut rng);
+        let shuffled = first.apply(&grid);
+        assert!(same_cells(&first.inverse().apply(&shuffled), &grid));
+        assert!(same_cells(
+            &first.compose(&second).apply(&grid),
+            &second.apply(&shuffled)
+        ));
+        assert_eq!(first.compose(&first.inverse()), Transform::identity());
+        assert_eq!(first.inverse().compose(&first), Transform::identity());
+    }
+
+    let mask = grid.obfuscate(&mut rng);
+    let transform = Transform::random(&mut rng);
+    let moved = transform.apply_mask(mask);
+    for pos in Pos::iter::<3>() {
+        assert_eq!(moved[transform.map_pos(pos)], mask[pos]);
+    }
+    assert_eq!(transform.inverse().apply_mask(moved), mask);
+}
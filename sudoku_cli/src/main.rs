@@ -1,7 +1,16 @@
+mod play;
+
 use clap::Parser;
 use rand::{SeedableRng, rngs::SmallRng};
-use std::{path::PathBuf, time::Instant};
-use sudoku::{Cell, Defer, Pos, RngChild, Sudoku, char_to_value, mask_full};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use sudoku::{
+    Alphabet, AnySudoku, BatchOptions, BatchSummary, Booklet, Check, Grade, Layout, LoadingError,
+    Style, solve_batch,
+};
 
 #[derive(clap::Parser)]
 struct Args {
@@ -11,39 +20,201 @@ struct Args {
     seed: Option<u64>,
     #[arg(default_value_t = 100)]
     retry: usize,
+    /// Remember up to this many nogoods during the search, 0 disables learning
+    #[arg(short, long, default_value_t = 0)]
+    learn: usize,
+    /// Symbols of the values, in the order of the values
+    #[arg(short, long, default_value = "standard")]
+    alphabet: Symbols,
+    /// Custom symbols, one per value, like the letters of a word for a wordoku
+    #[arg(long)]
+    symbols: Option<String>,
+    /// Colour the givens and the solved values of the drawn grids
+    #[arg(long, default_value = "auto")]
+    color: Color,
+    /// Draw the grids with ASCII characters only
+    #[arg(long)]
+    ascii: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum Color {
+    /// When the output is a terminal
+    Auto,
+    Always,
+    Never,
 }
 
 #[derive(clap::Subcommand, Clone)]
 enum Command {
     Solve {
         input: PathBuf,
+        /// Guessed from the extension of the input when omitted
+        #[arg(short, long)]
+        format: Option<Format>,
     },
     Generate {
-        size: u32,
+        size: usize,
         #[arg(short, long)]
         sparse: bool,
+        #[arg(short, long, default_value = "grid")]
+        format: Format,
+        /// Scramble a fixed pattern instead of searching, instant even for the largest grids
+        #[arg(short, long)]
+        pattern: bool,
+    },
+    /// Report the conflicts, the number of solutions and the minimality of a puzzle
+    ///
+    /// The exit code is 0 for a single solution, 1 if the puzzle can't be read,
+    /// 2 if it has no solution, 3 if it has several and 4 when the search runs out of steps.
+    Check {
+        input: PathBuf,
+        /// Guessed from the extension of the input when omitted
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Stop counting the solutions past this many
+        #[arg(short, long, default_value_t = 2)]
+        max_solutions: usize,
+    },
+    /// Solve a puzzle with the logical techniques and rate its difficulty
+    Grade {
+        input: PathBuf,
+        /// Guessed from the extension of the input when omitted
+        #[arg(short, long)]
+        format: Option<Format>,
+    },
+    /// List the steps of the logical solve of a puzzle
+    Explain {
+        input: PathBuf,
+        /// Guessed from the extension of the input when omitted
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// Draw the pencil marks after each step
+        #[arg(short, long)]
+        candidates: bool,
+    },
+    /// Play a puzzle in the terminal, a generated one unless a file is given
+    Play {
+        input: Option<PathBuf>,
+        /// Guessed from the extension of the input when omitted
+        #[arg(short, long)]
+        format: Option<Format>,
+        /// The size of the generated puzzle, up to 5
+        #[arg(long, default_value_t = 3)]
+        size: usize,
+    },
+    /// Solve every puzzle of a collection, one per line, and report the throughput
+    Batch {
+        input: PathBuf,
+        /// Worker threads, 0 for one per core
+        #[arg(short, long, default_value_t = 1)]
+        threads: usize,
+        /// Stop counting the solutions of a puzzle past this many
+        #[arg(short, long, default_value_t = 2)]
+        max_solutions: usize,
+        /// Number of slowest puzzles listed at the end
+        #[arg(long, default_value_t = 5)]
+        slowest: usize,
+    },
+    /// Generate a printable PDF of puzzles, followed by their answers
+    Booklet {
+        size: usize,
+        output: PathBuf,
+        #[arg(short, long, default_value_t = 6)]
+        count: usize,
+        #[arg(short, long, default_value_t = 4)]
+        per_page: usize,
+        #[arg(short, long, default_value = "Sudoku")]
+        title: String,
     },
 }
 
-const GRID_SIZE_0: usize = 0000;
-const GRID_SIZE_1: usize = 0001;
-const GRID_SIZE_2: usize = 0016;
-const GRID_SIZE_3: usize = 0081;
-const GRID_SIZE_4: usize = 0256;
-const GRID_SIZE_5: usize = 0625;
-const GRID_SIZE_6: usize = 1296;
-const GRID_SIZE_7: usize = 2401;
-const GRID_SIZE_8: usize = 4096;
+/// Layout of the grids read and written
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// The drawn grid, read with `_` for blanks and whitespace between cells
+    Grid,
+    /// One puzzle per line, a file may hold many of them
+    Line,
+    /// SadMan Software `.sdk` layout
+    Sdk,
+    /// Simple Sudoku `.ss` layout
+    Ss,
+    /// Numbers from 1 separated by whitespace or commas, `0` or `.` for blanks
+    Numbers,
+}
+
+/// The predefined alphabets
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+enum Symbols {
+    /// `1` to `9`, `0`, then letters and more, up to 64 symbols
+    Standard,
+    /// `1` to `9`, then `A` to `Z`
+    Digits,
+    /// `0` to `F`
+    Hex,
+    /// `A` to `Z`
+    Letters,
+}
+
+impl Symbols {
+    fn alphabet(self) -> Alphabet {
+        match self {
+            Symbols::Standard => Alphabet::standard(),
+            Symbols::Digits => Alphabet::digits(),
+            Symbols::Hex => Alphabet::hex(),
+            Symbols::Letters => Alphabet::letters(),
+        }
+    }
+}
+
+impl Format {
+    fn of_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("sdk") => Format::Sdk,
+            Some("ss") => Format::Ss,
+            Some("csv") => Format::Numbers,
+            _ => Format::Grid,
+        }
+    }
+}
 
 fn main() {
     let Args {
         seed,
         command,
         retry,
+        learn,
+        alphabet,
+        symbols,
+        color,
+        ascii,
     } = Args::parse();
-    let seed = seed.unwrap_or_else(|| rand::random());
+    let seed = seed.unwrap_or_else(rand::random);
+    let alphabet = match symbols {
+        Some(symbols) => match Alphabet::new(symbols.chars()) {
+            Some(alphabet) => alphabet,
+            None => {
+                eprintln!("invalid symbols {symbols:?}, they must be distinct and not blanks.");
+                return;
+            }
+        },
+        None => alphabet.alphabet(),
+    };
+    let style = Style {
+        colors: match color {
+            Color::Auto => Style::detect().colors,
+            Color::Always => true,
+            Color::Never => false,
+        },
+        ascii,
+    };
+    let output = Output {
+        alphabet: &alphabet,
+        style,
+    };
     match command {
-        Command::Solve { input } => {
+        Command::Solve { input, format } => {
             let content = match std::fs::read_to_string(&input) {
                 Ok(content) => content,
                 Err(err) => {
@@ -51,94 +222,445 @@ fn main() {
                     return;
                 }
             };
-            let content: Vec<Option<u32>> = content
-                .chars()
-                .flat_map(|c| {
-                    if c == '_' {
-                        Some(None)
-                    } else {
-                        char_to_value(c).map(Some)
+            let format = format.unwrap_or_else(|| Format::of_path(&input));
+            for grid in load(&content, format, &alphabet) {
+                match grid {
+                    Ok(mut grid) => solve(&mut grid, seed, learn, format, &output),
+                    Err(err) => eprintln!("{}: {err}.", input.display()),
+                }
+            }
+        }
+        Command::Check {
+            input,
+            format,
+            max_solutions,
+        } => {
+            let content = match std::fs::read_to_string(&input) {
+                Ok(content) => content,
+                Err(err) => {
+                    eprintln!("Could not open {:?}: {}.", input, err);
+                    std::process::exit(1);
+                }
+            };
+            let format = format.unwrap_or_else(|| Format::of_path(&input));
+            let check = givens(&content, format, &alphabet)
+                .and_then(|values| Check::new(&values, max_solutions));
+            match check {
+                Ok(check) => std::process::exit(report(&check)),
+                Err(err) => {
+                    eprintln!("{}: {err}.", input.display());
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Grade { input, format } => {
+            let Some(values) = read_givens(&input, format, &alphabet) else {
+                return;
+            };
+            match AnySudoku::grade(&values) {
+                Ok(grade) => print_grade(&grade),
+                Err(err) => eprintln!("{}: {err}.", input.display()),
+            }
+        }
+        Command::Explain {
+            input,
+            format,
+            candidates,
+        } => {
+            let Some(values) = read_givens(&input, format, &alphabet) else {
+                return;
+            };
+            let mut stdout = std::io::stdout();
+            let mut n = 0;
+            let explained = AnySudoku::explain(&values, |step, grid| {
+                n += 1;
+                println!("{n}. {}", step.display(&alphabet));
+                if candidates {
+                    grid.print_candidates(&mut stdout, &alphabet, style)
+                        .unwrap();
+                }
+            });
+            match explained {
+                Ok(grade) => print_grade(&grade),
+                Err(err) => eprintln!("{}: {err}.", input.display()),
+            }
+        }
+        Command::Play {
+            input,
+            format,
+            size,
+        } => {
+            let givens = match input {
+                Some(input) => match read_givens(&input, format, &alphabet) {
+                    Some(givens) => givens,
+                    None => return,
+                },
+                None => {
+                    if !valid_size(size) {
+                        return;
                     }
-                })
-                .collect();
-            match content.len() {
-                GRID_SIZE_0 => solve::<0, GRID_SIZE_0>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_1 => solve::<1, GRID_SIZE_1>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_2 => solve::<2, GRID_SIZE_2>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_3 => solve::<3, GRID_SIZE_3>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_4 => solve::<4, GRID_SIZE_4>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_5 => solve::<5, GRID_SIZE_5>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_6 => solve::<6, GRID_SIZE_6>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_7 => solve::<7, GRID_SIZE_7>(seed, retry, content.try_into().unwrap()),
-                GRID_SIZE_8 => solve::<8, GRID_SIZE_8>(seed, retry, content.try_into().unwrap()),
-                _ => {
-                    eprintln!("invalid grid size");
+                    if size > play::MAX_SIZE {
+                        eprintln!(
+                            "Could not play: only sizes up to {} can be played.",
+                            play::MAX_SIZE
+                        );
+                        return;
+                    }
+                    let mut rng = SmallRng::seed_from_u64(seed);
+                    let solution = (0..retry)
+                        .find_map(|_| AnySudoku::generate_full(size, &mut rng, learn))
+                        .or_else(|| AnySudoku::generate_shuffled(size, &mut rng))
+                        .expect("the size is valid");
+                    let mask = solution.obfuscate(&mut rng);
+                    let values = solution.values().into_iter().zip(mask);
+                    values
+                        .map(|(value, shown)| value.filter(|_| shown))
+                        .collect()
+                }
+            };
+            // every value needs a key
+            let standard = Alphabet::standard();
+            let size = AnySudoku::size_of_len(givens.len()).unwrap_or_default();
+            let alphabet = if alphabet.covers(size) {
+                &alphabet
+            } else {
+                &standard
+            };
+            if let Err(err) = play::play(&givens, alphabet, style) {
+                eprintln!("Could not play: {err}.");
+            }
+        }
+        Command::Batch {
+            input,
+            threads,
+            max_solutions,
+            slowest,
+        } => {
+            let content = match std::fs::read_to_string(&input) {
+                Ok(content) => content,
+                Err(err) => {
+                    eprintln!("Could not open {:?}: {}.", input, err);
                     return;
                 }
             };
+            let mut puzzles = Vec::new();
+            let mut invalid = 0;
+            for grid in AnySudoku::from_collection(&content, &alphabet) {
+                match grid {
+                    Ok(grid) => puzzles.push(grid),
+                    Err(err) => {
+                        eprintln!("{}: {err}.", input.display());
+                        invalid += 1;
+                    }
+                }
+            }
+            let options = BatchOptions {
+                threads,
+                max_solutions: max_solutions.max(1),
+                capacity: learn,
+            };
+            batch(&puzzles, invalid, &options, slowest, &output);
         }
-        Command::Generate { size, sparse } => match size {
-            0 => generate::<0>(seed, retry, sparse),
-            1 => generate::<1>(seed, retry, sparse),
-            2 => generate::<2>(seed, retry, sparse),
-            3 => generate::<3>(seed, retry, sparse),
-            4 => generate::<4>(seed, retry, sparse),
-            5 => generate::<5>(seed, retry, sparse),
-            6 => generate::<6>(seed, retry, sparse),
-            7 => generate::<7>(seed, retry, sparse),
-            8 => generate::<8>(seed, retry, sparse),
-            _ => {
-                eprintln!("invalid grid size {size}, expecting one of 0, 1, 2, 3, 4, 5, 6, 7 or 8.")
+        Command::Generate {
+            size,
+            sparse,
+            format,
+            pattern,
+        } => {
+            if valid_size(size) {
+                let retry = if pattern { 0 } else { retry };
+                generate(size, seed, retry, sparse, learn, format, &output);
             }
-        },
+        }
+        Command::Booklet {
+            size,
+            output,
+            count,
+            per_page,
+            title,
+        } => {
+            if !valid_size(size) {
+                return;
+            }
+            let mut booklet = Booklet::new(&title, per_page);
+            let mut rng = SmallRng::seed_from_u64(seed);
+            for _ in 0..count {
+                let solution = (0..retry)
+                    .find_map(|_| AnySudoku::generate_full(size, &mut rng, learn))
+                    .or_else(|| AnySudoku::generate_shuffled(size, &mut rng))
+                    .expect("the size is valid");
+                let mask = solution.obfuscate(&mut rng);
+                let givens: Vec<Option<u32>> = solution
+                    .values()
+                    .into_iter()
+                    .zip(&mask)
+                    .map(|(value, given)| value.filter(|_| *given))
+                    .collect();
+                let difficulty = AnySudoku::grade(&givens)
+                    .expect("the givens come from a solution")
+                    .difficulty();
+                let title = format!("{size}x{size} #{}", booklet.len() + 1);
+                booklet.add_any(&title, &difficulty.to_string(), &solution, &mask);
+            }
+            let written = std::fs::File::create(&output)
+                .and_then(|file| booklet.write_pdf(std::io::BufWriter::new(file), &alphabet));
+            if let Err(err) = written {
+                eprintln!("Could not write {:?}: {}.", output, err);
+            }
+        }
     }
 }
 
-fn generate<const N: usize>(seed: u64, retry: usize, sparse: bool) {
-    for seed in (seed..).take(retry) {
-        let mut rng = SmallRng::seed_from_u64(seed);
-        let mut grid = Sudoku::<N>::default();
+fn valid_size(size: usize) -> bool {
+    let valid = AnySudoku::new(size).is_some();
+    if !valid {
+        eprintln!(
+            "invalid grid size {size}, expecting one of 1, 2, 3, 4, 5, 6, 7, 8, 9, 10 or 11."
+        );
+    }
+    valid
+}
 
-        let start = Instant::now();
-        if let Some(solution) = grid
-            .brute_force(rng.rng_child(), 0..Sudoku::<N>::TTL)
-            .next()
-        {
-            let elapsed = start.elapsed();
-            let mask = if sparse {
-                solution.obfuscate(&mut rng)
-            } else {
-                mask_full()
+fn load(
+    content: &str,
+    format: Format,
+    alphabet: &Alphabet,
+) -> Vec<Result<AnySudoku, LoadingError>> {
+    match format {
+        Format::Grid => {
+            // grids with more values than symbols are written with numbers
+            let grid = match AnySudoku::from_str_strict(content, alphabet) {
+                Err(LoadingError::InvalidSize { .. }) => AnySudoku::from_numbers(content),
+                loaded => loaded,
             };
+            vec![grid]
+        }
+        Format::Line => AnySudoku::from_collection(content, alphabet).collect(),
+        Format::Sdk => vec![AnySudoku::from_sdk(content, alphabet)],
+        Format::Ss => vec![AnySudoku::from_ss(content, alphabet)],
+        Format::Numbers => vec![AnySudoku::from_numbers(content)],
+    }
+}
 
-            solution.print(&mut std::io::stdout(), mask).unwrap();
-            println!("elapsed: {elapsed:?}");
-            return;
+// The givens as written, conflicting or not
+fn givens(
+    content: &str,
+    format: Format,
+    alphabet: &Alphabet,
+) -> Result<Vec<Option<u32>>, LoadingError> {
+    match format {
+        Format::Grid => match Layout::Strict.read(content, alphabet) {
+            Err(LoadingError::InvalidSize { .. }) => Layout::Numbers.read(content, alphabet),
+            read => read,
+        },
+        Format::Line => Layout::Line.read(content, alphabet),
+        Format::Sdk => Layout::Sdk.read(content, alphabet),
+        Format::Ss => Layout::Ss.read(content, alphabet),
+        Format::Numbers => Layout::Numbers.read(content, alphabet),
+    }
+}
+
+// The givens of the file, or `None` once the failure is reported
+fn read_givens(
+    input: &Path,
+    format: Option<Format>,
+    alphabet: &Alphabet,
+) -> Option<Vec<Option<u32>>> {
+    let content = match std::fs::read_to_string(input) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("Could not open {:?}: {}.", input, err);
+            return None;
+        }
+    };
+    let format = format.unwrap_or_else(|| Format::of_path(input));
+    givens(&content, format, alphabet)
+        .inspect_err(|err| eprintln!("{}: {err}.", input.display()))
+        .ok()
+}
+
+fn print_grade(grade: &Grade) {
+    println!("difficulty: {}", grade.difficulty());
+    match grade.solved {
+        true => println!("solved in {} steps", grade.steps),
+        false => println!("stuck after {} steps", grade.steps),
+    }
+    for (technique, count) in &grade.techniques {
+        println!("  {technique}: {count}");
+    }
+}
+
+// Print the findings of the check, and return the exit code
+fn report(check: &Check) -> i32 {
+    for conflict in &check.conflicts {
+        println!("conflict: {conflict}");
+    }
+    let cells = |cells: &[(usize, usize)]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .map(|(row, col)| format!("r{}c{}", row + 1, col + 1))
+            .collect();
+        cells.join(" ")
+    };
+    match (check.solutions, check.exhausted) {
+        (count, true) => println!("at least {count} solutions, the search ran out of steps"),
+        (0, false) => println!("no solution"),
+        (1, false) => println!("unique solution"),
+        (count, false) => println!("at least {count} solutions"),
+    }
+    if check.is_unique() {
+        if check.is_minimal() {
+            println!("minimal");
+        } else if !check.redundant.is_empty() {
+            println!("not minimal, removable givens: {}", cells(&check.redundant));
+        }
+        if !check.undecided.is_empty() {
+            println!("out of steps for the givens: {}", cells(&check.undecided));
+        }
+    }
+    match (check.solutions, check.exhausted) {
+        (0 | 1, true) => 4,
+        (0, false) => 2,
+        (1, false) => 0,
+        _ => 3,
+    }
+}
+
+// How the grids are written
+struct Output<'a> {
+    alphabet: &'a Alphabet,
+    style: Style,
+}
+
+fn write(
+    grid: &AnySudoku,
+    mask: &[bool],
+    givens: &[bool],
+    format: Format,
+    output: &Output,
+) -> Result<(), std::io::Error> {
+    let stdout = std::io::stdout();
+    let alphabet = output.alphabet;
+    let format = match format {
+        // one symbol per cell, the numbers suit any size
+        Format::Line | Format::Sdk | Format::Ss if !alphabet.covers(grid.size()) => {
+            eprintln!("not enough symbols for the grid, written as numbers");
+            Format::Numbers
         }
-        println!("retrying");
+        format => format,
+    };
+    match format {
+        Format::Grid => grid.print_solution(stdout, mask, givens, alphabet, output.style),
+        Format::Line => grid.write_line(stdout, mask, alphabet),
+        Format::Sdk => grid.write_sdk(stdout, mask, alphabet),
+        Format::Ss => grid.write_ss(stdout, mask, alphabet),
+        Format::Numbers => grid.write_numbers(stdout, mask),
     }
-    println!("exhausted {retry} attempts without finding a solution");
 }
 
-fn solve<const N: usize, const L: usize>(seed: u64, retry: usize, values: [Option<u32>; L]) {
-    assert_eq!(N * N * N * N, L);
-    let mut grid = Sudoku::<N>::default();
-    let mut defer = Defer::new();
-    for (pos, value) in Pos::iter::<N>().zip(values) {
-        let cell = match value {
-            Some(value) => Cell::from_value(value),
-            None => Cell::FULL,
+fn generate(
+    size: usize,
+    seed: u64,
+    retry: usize,
+    sparse: bool,
+    learn: usize,
+    format: Format,
+    output: &Output,
+) {
+    let emit = |solution: AnySudoku, mut rng: SmallRng, start: Instant| {
+        let elapsed = start.elapsed();
+        let mask = if sparse {
+            solution.obfuscate(&mut rng)
+        } else {
+            solution.mask_full()
         };
-        let Some(_) = grid.remove_all(!cell, pos, &mut defer) else {
-            eprintln!("conflicting value");
+
+        write(&solution, &mask, &mask, format, output).unwrap();
+        eprintln!("elapsed: {elapsed:?}");
+    };
+    for seed in (seed..).take(retry) {
+        let mut rng = SmallRng::seed_from_u64(seed);
+
+        let start = Instant::now();
+        if let Some(solution) = AnySudoku::generate_full(size, &mut rng, learn) {
+            emit(solution, rng, start);
             return;
-        };
+        }
+        eprintln!("retrying");
+    }
+    if retry > 0 {
+        eprintln!("exhausted {retry} attempts, scrambling the pattern instead");
+    }
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let start = Instant::now();
+    if let Some(solution) = AnySudoku::generate_shuffled(size, &mut rng) {
+        emit(solution, rng, start);
     }
+}
+
+fn solve(grid: &mut AnySudoku, seed: u64, learn: usize, format: Format, output: &Output) {
+    let mask = grid.mask_full();
+    let givens: Vec<bool> = grid.values().iter().map(Option::is_some).collect();
     for (i, solution) in grid
-        .brute_force(SmallRng::seed_from_u64(seed), std::iter::repeat(0))
+        .solutions(SmallRng::seed_from_u64(seed), std::iter::repeat(0), learn)
         .enumerate()
     {
-        solution.print(&mut std::io::stdout(), mask_full()).unwrap();
-        println!("nth = {}", i + 1);
+        write(&solution, &mask, &givens, format, output).unwrap();
+        eprintln!("nth = {}", i + 1);
+    }
+}
+
+fn batch(
+    puzzles: &[AnySudoku],
+    invalid: usize,
+    options: &BatchOptions,
+    slowest: usize,
+    output: &Output,
+) {
+    let start = Instant::now();
+    let solved = solve_batch(puzzles, options);
+    let summary = BatchSummary::new(&solved, start.elapsed(), slowest);
+
+    let alphabet = output.alphabet;
+    if puzzles.iter().any(|puzzle| !alphabet.covers(puzzle.size())) {
+        eprintln!("not enough symbols for the grids, written as numbers");
+    }
+    let mut stdout = std::io::stdout().lock();
+    for (i, solved) in solved.iter().enumerate() {
+        let count = match solved.count {
+            count if count >= options.max_solutions && count > 1 => format!("{count}+"),
+            count => count.to_string(),
+        };
+        write!(stdout, "{}\t{count}\t{:?}\t", i + 1, solved.elapsed).unwrap();
+        match &solved.solution {
+            Some(solution) if alphabet.covers(solution.size()) => solution
+                .write_line(&mut stdout, &solution.mask_full(), alphabet)
+                .unwrap(),
+            // one symbol per cell can't be written, the numbers stay on one line
+            Some(solution) => {
+                let numbers: Vec<String> = solution
+                    .values()
+                    .iter()
+                    .map(|value| value.map_or(0, |value| value + 1).to_string())
+                    .collect();
+                writeln!(stdout, "{}", numbers.join(" ")).unwrap();
+            }
+            None => writeln!(stdout, "-").unwrap(),
+        }
+    }
+    drop(stdout);
+
+    eprintln!(
+        "{} puzzles in {:?}, {:.1} puzzles/sec",
+        summary.puzzles,
+        summary.elapsed,
+        summary.per_second()
+    );
+    eprintln!(
+        "failures: {}, ambiguous: {}, invalid: {invalid}",
+        summary.failures, summary.ambiguous
+    );
+    for &i in &summary.slowest {
+        eprintln!("slow: #{} in {:?}", i + 1, solved[i].elapsed);
     }
 }
@@ -0,0 +1,571 @@
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io::Write;
+use sudoku::{
+    Alphabet, Board, Cell, Check, EditError, Logic, Pos, Shade, Size, Storage, Style, peers,
+};
+
+/// The biggest size the board fits on a screen, 25×25
+pub const MAX_SIZE: usize = 5;
+
+const HELP: &str = "arrows move, symbols enter, Tab pencil, Space clear, \
+                    ^Z undo, ^Y redo, ^T hint, ^E check, ^P marks, Esc quit";
+
+/// Play the puzzle in the terminal until the player quits
+pub fn play(givens: &[Option<u32>], alphabet: &Alphabet, style: Style) -> Result<(), String> {
+    let received = givens.len();
+    match sudoku::AnySudoku::size_of_len(received) {
+        Some(1) => run::<1>(givens, alphabet, style),
+        Some(2) => run::<2>(givens, alphabet, style),
+        Some(3) => run::<3>(givens, alphabet, style),
+        Some(4) => run::<4>(givens, alphabet, style),
+        Some(5) => run::<5>(givens, alphabet, style),
+        _ => Err(format!(
+            "{received} cells, only grids up to {0}x{0} can be played",
+            MAX_SIZE * MAX_SIZE
+        )),
+    }
+}
+
+fn run<const N: usize>(
+    givens: &[Option<u32>],
+    alphabet: &Alphabet,
+    style: Style,
+) -> Result<(), String>
+where
+    Size<N>: Storage,
+{
+    let mut game = Game::<N>::new(givens)?;
+    let mut stdout = std::io::stdout();
+    let _screen = Screen::enter(&mut stdout).map_err(|err| err.to_string())?;
+    game.play(&mut stdout, alphabet, style)
+        .map_err(|err| err.to_string())
+}
+
+// The terminal in raw mode on the alternate screen, restored when dropped
+struct Screen;
+
+impl Screen {
+    fn enter(stdout: &mut impl Write) -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(stdout, EnterAlternateScreen, Hide)?;
+        Ok(Screen)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        let _ = execute!(std::io::stdout(), Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+// The content of a cell before and after an edit, for undo and redo
+#[derive(Clone, Copy)]
+struct Edit<const N: usize>
+where
+    Size<N>: Storage,
+{
+    pos: Pos,
+    before: (Option<u32>, Cell<N>),
+    after: (Option<u32>, Cell<N>),
+}
+
+struct Game<const N: usize>
+where
+    Size<N>: Storage,
+{
+    /// The givens, with the candidates they leave
+    board: Board<N>,
+    /// The values entered by the player, in the order of [Pos::iter]
+    ///
+    /// They never clash, but aren't propagated: a wrong entry is
+    /// accepted, and only reported when the player checks or asks a hint.
+    entries: Vec<Option<u32>>,
+    /// The values of the solution, only when it is known to be unique
+    solution: Option<Vec<Option<u32>>>,
+    marks: Vec<Cell<N>>,
+    undo: Vec<Edit<N>>,
+    redo: Vec<Edit<N>>,
+    cursor: (usize, usize),
+    /// Symbols toggle pencil marks instead of entering values
+    pencil: bool,
+    /// Draw the pencil marks of every cell instead of the values
+    show_marks: bool,
+    /// The last refused entry, shown in red until the next key
+    refused: Option<EditError>,
+    message: String,
+}
+
+impl<const N: usize> Game<N>
+where
+    Size<N>: Storage,
+{
+    const CELLS: usize = N * N * N * N;
+
+    fn new(givens: &[Option<u32>]) -> Result<Self, String> {
+        let check = Check::count(givens, 2).map_err(|err| err.to_string())?;
+        if let Some(conflict) = check.conflicts.first() {
+            return Err(conflict.to_string());
+        }
+        let message = match (check.solutions, check.exhausted) {
+            (0, false) => return Err("the puzzle has no solution".to_string()),
+            (0, true) => "the search ran out of steps, hints can't reveal cells",
+            (1, false) => "",
+            (1, true) => "uniqueness couldn't be confirmed, hints can't reveal cells",
+            _ => "the puzzle has several solutions, hints can't reveal cells",
+        };
+        let givens = Pos::iter::<N>()
+            .zip(givens)
+            .filter_map(|(pos, value)| Some((pos, (*value)?)));
+        let board = Board::new(givens).map_err(|_| "the givens clash")?;
+        let solution = check
+            .is_unique()
+            .then(|| check.solution.map(|solution| solution.values()))
+            .flatten();
+        Ok(Self {
+            board,
+            solution,
+            entries: vec![None; Self::CELLS],
+            marks: vec![Cell::EMPTY; Self::CELLS],
+            undo: Vec::new(),
+            redo: Vec::new(),
+            cursor: (0, 0),
+            pencil: false,
+            show_marks: false,
+            refused: None,
+            message: message.to_string(),
+        })
+    }
+
+    fn play(
+        &mut self,
+        out: &mut impl Write,
+        alphabet: &Alphabet,
+        style: Style,
+    ) -> std::io::Result<()> {
+        loop {
+            self.draw(out, alphabet, style)?;
+            match crossterm::event::read()? {
+                Event::Key(key) if key.kind != KeyEventKind::Release => {
+                    self.message.clear();
+                    self.refused = None;
+                    if !self.key(key, alphabet) {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // Handle a key, returning `false` to quit
+    fn key(&mut self, key: KeyEvent, alphabet: &Alphabet) -> bool {
+        let nn = N * N;
+        let (row, col) = self.cursor;
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            match key.code {
+                KeyCode::Char('z') => self.undo(),
+                KeyCode::Char('y' | 'r') => self.redo(),
+                KeyCode::Char('t') => self.hint(alphabet),
+                KeyCode::Char('e') => self.check(),
+                KeyCode::Char('p') => self.show_marks = !self.show_marks,
+                KeyCode::Char('c' | 'q') => return false,
+                _ => {}
+            }
+            return true;
+        }
+        match key.code {
+            KeyCode::Esc => return false,
+            KeyCode::Up => self.cursor = ((row + nn - 1) % nn, col),
+            KeyCode::Down => self.cursor = ((row + 1) % nn, col),
+            KeyCode::Left => self.cursor = (row, (col + nn - 1) % nn),
+            KeyCode::Right => self.cursor = (row, (col + 1) % nn),
+            KeyCode::Tab => self.pencil = !self.pencil,
+            KeyCode::Backspace | KeyCode::Delete | KeyCode::Char(' ') => self.clear(),
+            KeyCode::Char(c) => {
+                let value = alphabet
+                    .value(c)
+                    .or_else(|| alphabet.value(c.to_ascii_uppercase()))
+                    .filter(|value| *value < nn as u32);
+                match value {
+                    Some(value) if self.pencil => self.toggle_mark(value),
+                    Some(value) => self.enter(value),
+                    None if c == '.' => self.clear(),
+                    None => self.message = format!("{c:?} is not a symbol of the grid"),
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn pos(&self) -> Pos {
+        let (row, col) = self.cursor;
+        Pos::from_row_col::<N>(row, col).expect("the cursor stays on the grid")
+    }
+
+    // The value of a cell, given or entered
+    fn value(&self, pos: Pos) -> Option<u32> {
+        self.board.value(pos).or(self.entries[pos.index::<N>()])
+    }
+
+    // The entry and the marks of a cell
+    fn state(&self, pos: Pos) -> (Option<u32>, Cell<N>) {
+        let i = pos.index::<N>();
+        (self.entries[i], self.marks[i])
+    }
+
+    // Only a clash refuses an entry, whether it leads to the solution isn't checked
+    fn apply(&mut self, pos: Pos, (entry, marks): (Option<u32>, Cell<N>)) -> Result<(), EditError> {
+        if self.board.is_given(pos) {
+            return Err(EditError::Given { pos });
+        }
+        if let Some(value) = entry {
+            if value >= Cell::<N>::R {
+                return Err(EditError::InvalidValue { value });
+            }
+            let clash = peers::<N>(pos).find(|other| self.value(*other) == Some(value));
+            if let Some(other) = clash {
+                return Err(EditError::Clash { pos, other, value });
+            }
+        }
+        let i = pos.index::<N>();
+        self.entries[i] = entry;
+        self.marks[i] = marks;
+        Ok(())
+    }
+
+    // Replace the entry and the marks of the cell under the cursor
+    fn edit(&mut self, after: (Option<u32>, Cell<N>)) {
+        let pos = self.pos();
+        if self.board.is_given(pos) {
+            self.message = "this cell holds a given".to_string();
+            return;
+        }
+        let before = self.state(pos);
+        if before == after {
+            return;
+        }
+        if let Err(err) = self.apply(pos, after) {
+            self.refuse(err);
+            return;
+        }
+        self.undo.push(Edit { pos, before, after });
+        self.redo.clear();
+        self.congratulate();
+    }
+
+    fn refuse(&mut self, err: EditError) {
+        self.message = match err {
+            EditError::Clash { other, .. } => {
+                let (row, col) = other.row_col::<N>();
+                format!("r{}c{} already holds this value", row + 1, col + 1)
+            }
+            EditError::Given { .. } => "this cell holds a given".to_string(),
+            EditError::Contradiction { .. } => "this value is ruled out".to_string(),
+            EditError::InvalidValue { value } => format!("{value} is not a value"),
+        };
+        self.refused = Some(err);
+    }
+
+    // The value drawn in a cell, in red for a refused entry and the cell it clashed with
+    fn shown(&self, pos: Pos) -> Option<(u32, Shade)> {
+        match self.refused {
+            Some(EditError::Clash { pos: at, value, .. }) if at == pos => {
+                return Some((value, Shade::Conflict));
+            }
+            Some(EditError::Clash { other, .. }) if other == pos => {
+                return Some((self.value(pos)?, Shade::Conflict));
+            }
+            _ => {}
+        }
+        let shade = match self.board.is_given(pos) {
+            true => Shade::Given,
+            false => Shade::Entry,
+        };
+        Some((self.value(pos)?, shade))
+    }
+
+    fn enter(&mut self, value: u32) {
+        let (_, marks) = self.state(self.pos());
+        self.edit((Some(value), marks));
+    }
+
+    fn toggle_mark(&mut self, value: u32) {
+        let (entry, marks) = self.state(self.pos());
+        let marks = match marks.contains(value) {
+            true => marks - value,
+            false => marks | Cell::from_value(value),
+        };
+        self.edit((entry, marks));
+    }
+
+    // The entry first, then the marks
+    fn clear(&mut self) {
+        match self.state(self.pos()) {
+            (Some(_), marks) => self.edit((None, marks)),
+            (None, _) => self.edit((None, Cell::EMPTY)),
+        }
+    }
+
+    fn undo(&mut self) {
+        let Some(edit) = self.undo.pop() else {
+            self.message = "nothing to undo".to_string();
+            return;
+        };
+        self.cursor = edit.pos.row_col::<N>();
+        // the cell held it before the edit, so it doesn't clash
+        if let Err(err) = self.apply(edit.pos, edit.before) {
+            self.refuse(err);
+            return;
+        }
+        self.redo.push(edit);
+    }
+
+    fn redo(&mut self) {
+        let Some(edit) = self.redo.pop() else {
+            self.message = "nothing to redo".to_string();
+            return;
+        };
+        self.cursor = edit.pos.row_col::<N>();
+        // the cell held it after the edit, so it doesn't clash
+        if let Err(err) = self.apply(edit.pos, edit.after) {
+            self.refuse(err);
+            return;
+        }
+        self.undo.push(edit);
+    }
+
+    // A full grid without a clash is a solution, whichever one it is
+    fn congratulate(&mut self) {
+        if self.left() == 0 {
+            self.message = "solved, well done!".to_string();
+        }
+    }
+
+    // The number of cells without a value
+    fn left(&self) -> usize {
+        self.values().iter().filter(|value| value.is_none()).count()
+    }
+
+    fn values(&self) -> Vec<Option<u32>> {
+        Pos::iter::<N>().map(|pos| self.value(pos)).collect()
+    }
+
+    // Whether the entries still lead to a solution
+    fn check(&mut self) {
+        let left = self.left();
+        let check = Check::count(&self.values(), 1).expect("the board makes a grid");
+        self.message = match (check.solutions, check.exhausted) {
+            _ if left == 0 => "solved, well done!".to_string(),
+            (0, false) => format!("no solution goes through the entries, {left} cells left"),
+            (0, true) => format!("the search ran out of steps, {left} cells left"),
+            _ => format!("no mistake so far, {left} cells left"),
+        };
+    }
+
+    // Point out a mistake, or the next step of the logical solver,
+    // or reveal a cell when no technique applies,
+    // only using the solution when it is unique
+    fn hint(&mut self, alphabet: &Alphabet) {
+        let wrong = |pos: Pos, solution: &[Option<u32>]| {
+            let (entry, _) = self.state(pos);
+            entry.is_some() && entry != solution[pos.index::<N>()]
+        };
+        if let Some(solution) = &self.solution
+            && let Some(pos) = Pos::iter::<N>().find(|pos| wrong(*pos, solution))
+        {
+            self.cursor = pos.row_col::<N>();
+            self.message = "this entry is wrong".to_string();
+            return;
+        }
+        let Ok(mut logic) = Logic::<N>::from_values(&self.values()) else {
+            self.message = "no solution goes through the entries".to_string();
+            return;
+        };
+        if let Some(step) = logic.step() {
+            let target = step
+                .placed
+                .map(|(pos, _)| pos)
+                .or(step.cells.first().copied());
+            if let Some(cursor) = target {
+                self.cursor = cursor;
+            }
+            self.message = format!("hint: {}", step.display(alphabet));
+            return;
+        }
+        let Some(solution) = &self.solution else {
+            self.message = "no technique applies".to_string();
+            return;
+        };
+        let Some(pos) = Pos::iter::<N>().find(|pos| self.value(*pos).is_none()) else {
+            return;
+        };
+        let value = solution[pos.index::<N>()].expect("the solution is full");
+        self.cursor = pos.row_col::<N>();
+        self.enter(value);
+        if self.message.is_empty() {
+            self.message = "no technique applies, the cell is revealed".to_string();
+        }
+    }
+
+    fn draw(&self, out: &mut impl Write, alphabet: &Alphabet, style: Style) -> std::io::Result<()> {
+        let mut screen = Vec::new();
+        let grid = self.board.grid();
+        if self.show_marks {
+            grid.print_marks(&mut screen, alphabet, style, |pos, _| {
+                self.value(pos).ok_or(self.marks[pos.index::<N>()])
+            })?;
+        } else {
+            grid.print_styled(&mut screen, alphabet, style, |pos, _| self.shown(pos))?;
+        }
+        let (row, col) = self.cursor;
+        let marks: Vec<char> = self.marks[self.pos().index::<N>()]
+            .filter_map(|value| alphabet.symbol(value))
+            .collect();
+        let mode = if self.pencil { "pencil" } else { "value" };
+        writeln!(
+            screen,
+            "r{}c{}  {mode}  marks: {}",
+            row + 1,
+            col + 1,
+            String::from_iter(marks)
+        )?;
+        writeln!(screen, "{}", self.message)?;
+        writeln!(screen, "{HELP}")?;
+
+        queue!(out, Clear(ClearType::All), MoveTo(0, 0))?;
+        for line in String::from_utf8_lossy(&screen).lines() {
+            write!(out, "{line}\r\n")?;
+        }
+        let (x, y) = self.screen_pos(alphabet);
+        queue!(out, MoveTo(x, y), Show)?;
+        out.flush()
+    }
+
+    // Where the cursor lands on the drawing of the grid
+    fn screen_pos(&self, alphabet: &Alphabet) -> (u16, u16) {
+        let (row, col) = self.cursor;
+        let (x, y) = if self.show_marks {
+            let columns = (0..(N * N) as u32)
+                .filter_map(|value| alphabet.width(value))
+                .max()
+                .unwrap_or(1) as usize;
+            let width = N * (columns + 1) + 1;
+            (col * (width + 1) + 1 + width / 2, 1 + row * (N + 1) + N / 2)
+        } else {
+            (col * 4 + 2, 1 + row * 2)
+        };
+        (x as u16, y as u16)
+    }
+}
+
+#[cfg(test)]
+fn givens_4x4(puzzle: &str) -> Vec<Option<u32>> {
+    let cells = puzzle.chars().filter(|c| *c != ' ');
+    cells.map(|c| c.to_digit(10)).collect()
+}
+
+#[test]
+fn edit_undo_redo() {
+    let at = |row, col| Pos::from_row_col::<2>(row, col).unwrap();
+    let mut game = Game::<2>::new(&givens_4x4("0.23 23.1 .032 321.")).unwrap();
+    assert!(game.solution.is_some());
+
+    game.cursor = (0, 1);
+    game.enter(1);
+    game.cursor = (3, 3);
+    game.toggle_mark(0);
+    assert_eq!(game.value(at(0, 1)), Some(1));
+    assert_eq!(game.marks[at(3, 3).index::<2>()], Cell::from_value(0));
+
+    game.undo();
+    game.undo();
+    assert_eq!(game.value(at(0, 1)), None);
+    assert_eq!(game.cursor, (0, 1));
+    game.undo();
+    assert_eq!(game.message, "nothing to undo");
+
+    game.redo();
+    assert_eq!(game.value(at(0, 1)), Some(1));
+    game.redo();
+    assert_eq!(game.marks[at(3, 3).index::<2>()], Cell::from_value(0));
+    assert_eq!(game.cursor, (3, 3));
+
+    // a new edit forgets what was undone
+    game.undo();
+    game.cursor = (1, 2);
+    game.enter(0);
+    game.redo();
+    assert_eq!(game.message, "nothing to redo");
+
+    game.cursor = (2, 0);
+    game.enter(1);
+    game.cursor = (3, 3);
+    game.enter(0);
+    assert_eq!(game.message, "solved, well done!");
+}
+
+#[test]
+fn only_clashes_are_refused() {
+    let at = |row, col| Pos::from_row_col::<2>(row, col).unwrap();
+    let mut game = Game::<2>::new(&givens_4x4("01.. ..0. ...2 3..0")).unwrap();
+    assert!(game.solution.is_some());
+
+    // 0 is already in the row
+    game.cursor = (0, 2);
+    game.enter(0);
+    assert_eq!(game.value(at(0, 2)), None);
+    assert_eq!(game.message, "r1c1 already holds this value");
+    assert_eq!(game.shown(at(0, 2)), Some((0, Shade::Conflict)));
+    assert_eq!(game.shown(at(0, 0)), Some((0, Shade::Conflict)));
+    assert_eq!(game.shown(at(0, 1)), Some((1, Shade::Given)));
+    assert!(game.undo.is_empty());
+
+    // the solution holds 2, but 3 doesn't clash, so it is accepted
+    game.refused = None;
+    game.message.clear();
+    game.enter(3);
+    assert_eq!(game.value(at(0, 2)), Some(3));
+    assert_eq!(game.shown(at(0, 2)), Some((3, Shade::Entry)));
+    assert!(game.message.is_empty());
+    assert_eq!(game.undo.len(), 1);
+
+    // and only reported when asked
+    game.check();
+    assert_eq!(
+        game.message,
+        "no solution goes through the entries, 9 cells left"
+    );
+    game.cursor = (3, 3);
+    game.hint(&Alphabet::standard());
+    assert_eq!(game.cursor, (0, 2));
+    assert_eq!(game.message, "this entry is wrong");
+
+    game.cursor = (0, 0);
+    game.enter(1);
+    assert_eq!(game.message, "this cell holds a given");
+}
+
+#[test]
+fn several_solutions() {
+    let at = |row, col| Pos::from_row_col::<2>(row, col).unwrap();
+    let mut game = Game::<2>::new(&givens_4x4(".... .... .... ....")).unwrap();
+    assert!(game.solution.is_none());
+
+    // any entry that keeps a solution is right
+    game.cursor = (0, 0);
+    game.enter(3);
+    game.check();
+    assert_eq!(game.message, "no mistake so far, 15 cells left");
+    game.hint(&Alphabet::standard());
+    assert_eq!(game.value(at(0, 0)), Some(3));
+    assert_eq!(game.message, "no technique applies");
+
+    assert!(Game::<2>::new(&givens_4x4("01.. .... 2... 3...")).is_err());
+}